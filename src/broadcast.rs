@@ -0,0 +1,142 @@
+use crate::Frostty;
+use crate::workspace::Workspace;
+
+/// Which panes receive the input typed into any one of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Broadcast {
+    /// Every pane in the same workspace.
+    Workspace,
+    /// Every pane marked for broadcasting, across workspaces and windows.
+    Marked,
+    All,
+}
+
+impl Broadcast {
+    /// The scope after `broadcast` when cycling, ending with broadcasting off.
+    pub fn next(broadcast: Option<Self>) -> Option<Self> {
+        match broadcast {
+            None => Some(Broadcast::Workspace),
+            Some(Broadcast::Workspace) => Some(Broadcast::Marked),
+            Some(Broadcast::Marked) => Some(Broadcast::All),
+            Some(Broadcast::All) => None,
+        }
+    }
+}
+
+impl Frostty {
+    /// Terminals that input typed into `id` is repeated to. Empty unless `id`
+    /// is itself shown in a broadcasting pane.
+    pub fn broadcast_targets(&self, id: u64) -> Vec<u64> {
+        let Some(broadcast) = self.broadcast else {
+            return vec![];
+        };
+        let workspaces = self.windows.iter().flat_map(|(window, state)| {
+            state
+                .workspaces
+                .iter()
+                .map(move |(index, workspace)| ((*window, *index), workspace))
+        });
+        targets(broadcast, id, workspaces)
+    }
+}
+
+/// The terminals in `workspaces`, each given with its place, that `id`
+/// repeats its input to under `broadcast`.
+fn targets<'a, P: PartialEq + Copy>(
+    broadcast: Broadcast,
+    id: u64,
+    workspaces: impl Iterator<Item = (P, &'a Workspace)> + Clone,
+) -> Vec<u64> {
+    let source = workspaces.clone().find_map(|(place, workspace)| {
+        workspace
+            .panes
+            .iter()
+            .any(|(_, pane)| pane.tabs.contains(&(id as usize)))
+            .then_some(place)
+    });
+    let members: Vec<u64> = workspaces
+        .filter(|(place, _)| broadcast != Broadcast::Workspace || Some(*place) == source)
+        .flat_map(|(_, workspace)| workspace.panes.iter())
+        .filter(|(_, pane)| broadcast != Broadcast::Marked || pane.marked)
+        .map(|(_, pane)| pane.id as u64)
+        .collect();
+
+    if !members.contains(&id) {
+        return vec![];
+    }
+    members.into_iter().filter(|member| *member != id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tiling::Tiling;
+
+    /// Workspace `0` holding terminals `0` to `2` with `1` marked, and
+    /// workspace `1` holding `3` and a marked `4`.
+    fn workspaces() -> Vec<Workspace> {
+        let mut workspaces = vec![
+            Workspace::tiled(Tiling::Tile, &[0, 1, 2]),
+            Workspace::tiled(Tiling::Tile, &[3, 4]),
+        ];
+        for (workspace, id) in [(0, 1), (1, 4)] {
+            let pane = workspaces[workspace].find(id).unwrap();
+            workspaces[workspace].panes.get_mut(pane).unwrap().marked = true;
+        }
+        workspaces
+    }
+
+    fn sorted_targets(broadcast: Broadcast, id: u64) -> Vec<u64> {
+        let workspaces = workspaces();
+        let mut targets = targets(broadcast, id, workspaces.iter().enumerate());
+        targets.sort();
+        targets
+    }
+
+    #[test]
+    fn cycling_ends_with_broadcasting_off() {
+        let mut broadcast = None;
+        let mut seen = vec![];
+        loop {
+            broadcast = Broadcast::next(broadcast);
+            seen.push(broadcast);
+            if broadcast.is_none() {
+                break;
+            }
+        }
+        assert_eq!(
+            seen,
+            [
+                Some(Broadcast::Workspace),
+                Some(Broadcast::Marked),
+                Some(Broadcast::All),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn workspace_broadcasts_stay_in_the_workspace() {
+        assert_eq!(sorted_targets(Broadcast::Workspace, 0), [1, 2]);
+        assert_eq!(sorted_targets(Broadcast::Workspace, 4), [3]);
+    }
+
+    #[test]
+    fn marked_broadcasts_only_reach_marked_panes() {
+        assert_eq!(sorted_targets(Broadcast::Marked, 1), [4]);
+        assert_eq!(sorted_targets(Broadcast::Marked, 0), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn broadcasting_to_all_reaches_every_pane() {
+        assert_eq!(sorted_targets(Broadcast::All, 3), [0, 1, 2, 4]);
+    }
+
+    #[test]
+    fn hidden_tabs_do_not_broadcast() {
+        let mut workspaces = workspaces();
+        let pane = workspaces[0].find(0).unwrap();
+        workspaces[0].panes.get_mut(pane).unwrap().tabs.push(7);
+        assert!(targets(Broadcast::All, 7, workspaces.iter().enumerate()).is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::fs;
 
 use serde::Deserialize;
@@ -7,10 +8,21 @@ pub struct Config {
     pub colors: Option<Colors>,
     pub general: Option<General>,
     pub border: Option<Border>,
+    #[allow(dead_code)]
     pub keybinds: Option<Keybinds>,
     pub window: Option<Window>,
     pub font: Option<Font>,
     pub bell: Option<Bell>,
+    pub layouts: Option<Vec<Layout>>,
+    pub daemon: Option<Daemon>,
+    pub tiling: Option<Tiling>,
+    pub scratchpad: Option<Scratchpad>,
+    pub confirm_close: Option<ConfirmClose>,
+    pub titles: Option<Titles>,
+    pub monitor: Option<Monitor>,
+    pub paste: Option<Paste>,
+    pub selection: Option<Selection>,
+    pub keyboard: Option<Keyboard>,
 }
 
 #[derive(Deserialize, Clone)]
@@ -57,9 +69,109 @@ pub struct Bell {
     pub duration: Option<u64>,
     pub color: Option<String>,
     pub command: Option<Command>,
+    pub command_interval: Option<u64>,
+    pub urgent: Option<bool>,
 }
 
 #[derive(Deserialize, Clone)]
+pub struct Paste {
+    pub confirm: Option<bool>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Keyboard {
+    pub app_modifier: Option<String>,
+    pub meta: Option<String>,
+    pub option_as_alt: Option<String>,
+    pub kitty_protocol: Option<bool>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Selection {
+    pub semantic_separators: Option<String>,
+    pub trim_whitespace: Option<bool>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Monitor {
+    pub silence: Option<u64>,
+    pub command: Option<Command>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Daemon {
+    pub attach: Option<bool>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Tiling {
+    pub layout: Option<crate::tiling::Tiling>,
+    pub master_ratio: Option<f32>,
+    pub master_count: Option<usize>,
+    pub resize_step: Option<f32>,
+    pub sticky_pins: Option<bool>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Scratchpad {
+    pub command: Option<Command>,
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ConfirmClose {
+    pub enabled: Option<bool>,
+    pub ignore: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Titles {
+    pub window: Option<String>,
+    pub pane: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Layout {
+    pub name: String,
+    pub keybind: Option<String>,
+    pub root: LayoutNode,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+pub enum LayoutNode {
+    Split(LayoutSplit),
+    Pane(LayoutPane),
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LayoutSplit {
+    pub axis: LayoutAxis,
+    pub ratio: Option<f32>,
+    pub a: Box<LayoutNode>,
+    pub b: Box<LayoutNode>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum LayoutAxis {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LayoutPane {
+    pub command: Option<Command>,
+    pub cwd: Option<String>,
+    pub env: Option<HashMap<String, String>>,
+    pub focus: Option<bool>,
+}
+
+#[derive(Deserialize, Clone)]
+#[allow(dead_code)]
 pub struct Keybinds {
     pub new: Option<String>,
     pub close: Option<String>,
@@ -79,6 +191,9 @@ pub struct App {
     pub background: Option<String>,
     pub active: Option<String>,
     pub inactive: Option<String>,
+    pub pinned: Option<String>,
+    pub broadcast: Option<String>,
+    pub alert: Option<String>,
 }
 
 #[derive(Deserialize, Clone)]
@@ -112,7 +227,12 @@ impl Config {
                 return None;
             }
         };
-        let config: Config = toml::from_str(&contents).expect("failed to parse config");
-        Some(config)
+        match toml::from_str(&contents) {
+            Ok(config) => Some(config),
+            Err(err) => {
+                eprintln!("failed to parse config, using the defaults: {}", err);
+                None
+            }
+        }
     }
 }
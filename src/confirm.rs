@@ -0,0 +1,175 @@
+use iced::widget::{button, column, container, row, text};
+use iced::{Element, Fill, Task, window};
+
+use crate::workspace::Pane;
+use crate::{Frostty, Message, style};
+
+impl Pane {
+    /// Whether the shown terminal can be closed, asking first when `running`
+    /// names a program still in it. Closing again while asking goes ahead.
+    pub fn confirm_close(&mut self, running: Option<String>) -> bool {
+        match running {
+            Some(running) if self.confirm.is_none() => {
+                self.confirm = Some(running);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Like [`Pane::confirm_close`], for closing the other panes while
+    /// `running` lists the programs still in them.
+    pub fn confirm_close_others(&mut self, running: Vec<String>) -> bool {
+        if self.confirm_others.take().is_some() || running.is_empty() {
+            return true;
+        }
+        self.confirm_others = Some(running);
+        false
+    }
+}
+
+impl Frostty {
+    /// The command running in terminal `id` that closing it would end, if
+    /// closing should ask about it first.
+    pub fn running_command(&self, id: u64) -> Option<String> {
+        let ignore = self.confirm_close.as_ref()?;
+        let process = self.terminals.get(&id)?.foreground_process()?;
+        (!ignore.contains(&process.name)).then_some(process.command)
+    }
+
+    /// Commands that closing window `id` would end. Daemon sessions and the
+    /// scratchpad outlive the window, so they aren't included.
+    fn window_commands(&self, id: window::Id) -> Vec<String> {
+        let Some(window) = self.windows.get(&id) else {
+            return vec![];
+        };
+        window
+            .workspaces
+            .values()
+            .flat_map(|workspace| {
+                let tiled = workspace
+                    .panes
+                    .iter()
+                    .flat_map(|(_, pane)| pane.tabs.iter().copied());
+                let floating = workspace
+                    .floating
+                    .iter()
+                    .map(|float| float.pane.id)
+                    .filter(|id| Some(*id) != self.scratchpad);
+                tiled.chain(floating)
+            })
+            .filter(|id| {
+                self.terminals
+                    .get(&(*id as u64))
+                    .is_some_and(|terminal| !terminal.is_remote())
+            })
+            .filter_map(|id| self.running_command(id as u64))
+            .collect()
+    }
+
+    /// Closes terminal `id` after its prompt was answered.
+    pub fn close_confirmed(&mut self, id: usize) -> Task<Message> {
+        let focus = if let Some((window, _, pane)) = self.locate(id as u64) {
+            self.window = window;
+            self.focus_pane(pane)
+        } else if let Some((window, _)) = self.locate_float(id) {
+            self.window = window;
+            self.focus_float(id)
+        } else {
+            return Task::none();
+        };
+        // Already asked, so closing the now focused terminal goes ahead.
+        Task::batch([focus, self.update(Message::CloseFocused)])
+    }
+
+    /// Closes the panes beside terminal `id` after its prompt was answered.
+    pub fn close_others_confirmed(&mut self, id: usize) -> Task<Message> {
+        let Some((window, _, pane)) = self.locate(id as u64) else {
+            return Task::none();
+        };
+        self.window = window;
+        let focus = self.focus_pane(pane);
+        // Already asked, so closing the others now goes ahead.
+        Task::batch([focus, self.update(Message::CloseOthers)])
+    }
+
+    /// Closes window `id`, asking first while programs are running in it.
+    pub fn request_window_close(&mut self, id: window::Id) -> Task<Message> {
+        let running = self.window_commands(id);
+        if let Some(window) = self.windows.get_mut(&id)
+            && window.confirm.is_none()
+            && !running.is_empty()
+        {
+            window.confirm = Some(running);
+            return Task::none();
+        }
+        window::close(id)
+    }
+}
+
+/// A prompt asking `question` about `lines`, answered with `confirm` or
+/// `cancel`.
+pub fn view_confirm<'a>(
+    question: &'a str,
+    lines: &[String],
+    action: &'a str,
+    confirm: Message,
+    cancel: Message,
+) -> Element<'a, Message> {
+    let lines = lines.iter().map(|line| text(line.clone()).into());
+    let prompt = column![
+        text(question),
+        column(lines).padding([0, 10]),
+        row![
+            button(action).style(button::danger).on_press(confirm),
+            button("Cancel").style(button::secondary).on_press(cancel),
+        ]
+        .spacing(10),
+    ]
+    .spacing(10);
+
+    container(container(prompt).padding(15).style(style::confirm))
+        .center(Fill)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_terminals_close_without_asking() {
+        let mut pane = Pane::new(0);
+        assert!(pane.confirm_close(None));
+        assert_eq!(pane.confirm, None);
+    }
+
+    #[test]
+    fn running_programs_ask_once_before_closing() {
+        let mut pane = Pane::new(0);
+        assert!(!pane.confirm_close(Some("vim notes".to_string())));
+        assert_eq!(pane.confirm.as_deref(), Some("vim notes"));
+        // Closing again while asking goes ahead.
+        assert!(pane.confirm_close(Some("vim notes".to_string())));
+    }
+
+    #[test]
+    fn closing_others_asks_once_about_every_program() {
+        let mut pane = Pane::new(0);
+        assert!(pane.confirm_close_others(vec![]));
+
+        let running = vec!["make".to_string(), "htop".to_string()];
+        assert!(!pane.confirm_close_others(running.clone()));
+        assert_eq!(pane.confirm_others, Some(running.clone()));
+        assert!(pane.confirm_close_others(running));
+        assert_eq!(pane.confirm_others, None);
+    }
+
+    #[test]
+    fn a_stale_prompt_for_others_is_cleared() {
+        let mut pane = Pane::new(0);
+        pane.confirm_others = Some(vec!["make".to_string()]);
+        assert!(pane.confirm_close_others(vec![]));
+        assert_eq!(pane.confirm_others, None);
+    }
+}
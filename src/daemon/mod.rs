@@ -0,0 +1,478 @@
+//! `frostty --daemon`: a background server that owns the PTYs and terminal
+//! state of every session, so shells outlive the windows attached to them.
+
+pub mod protocol;
+mod snapshot;
+
+use crate::terminal::{MouseEncodings, Scanner};
+use crate::{config, terminal};
+use alacritty_terminal::event::{Event, EventListener, OnResize, WindowSize};
+use alacritty_terminal::sync::FairMutex;
+use alacritty_terminal::term::{self, Term, test::TermSize};
+use alacritty_terminal::tty::{self, Pty};
+use alacritty_terminal::vte::ansi::Processor;
+use protocol::{Reply, Request, SessionInfo};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TryRecvError, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+type Sessions = Arc<Mutex<HashMap<u64, Arc<Session>>>>;
+
+/// Replies queued for a client before it falls behind and is sent a fresh
+/// snapshot instead once it catches up.
+const CLIENT_QUEUE: usize = 256;
+
+/// How long writing to a client may block before it is dropped.
+const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Runs the daemon in the foreground until it is killed.
+pub fn run() -> io::Result<()> {
+    let path = protocol::socket_path();
+    if UnixStream::connect(&path).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("a daemon is already listening on {}", path.display()),
+        ));
+    }
+    private_dir(&protocol::socket_dir())?;
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+
+    let sessions = Sessions::default();
+    let next_id = Arc::new(AtomicU64::new(0));
+    let config = Arc::new(terminal::term_config(config::Config::new()));
+    let uid = unsafe { libc::getuid() };
+    for stream in listener.incoming() {
+        let stream = stream?;
+        if peer_uid(&stream).ok() != Some(uid) {
+            continue;
+        }
+        let sessions = sessions.clone();
+        let next_id = next_id.clone();
+        let config = config.clone();
+        thread::spawn(move || {
+            let _ = serve(stream, &sessions, &next_id, &config);
+        });
+    }
+
+    Ok(())
+}
+
+/// Connects to a running daemon, starting one in the background if there is
+/// none yet.
+pub fn connect() -> io::Result<UnixStream> {
+    let path = protocol::socket_path();
+    if let Ok(stream) = UnixStream::connect(&path) {
+        return owned(stream);
+    }
+
+    use std::os::unix::process::CommandExt;
+    std::process::Command::new(std::env::current_exe()?)
+        .arg("--daemon")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .process_group(0)
+        .spawn()?;
+
+    for _ in 0..50 {
+        thread::sleep(Duration::from_millis(20));
+        if let Ok(stream) = UnixStream::connect(&path) {
+            return owned(stream);
+        }
+    }
+    owned(UnixStream::connect(&path)?)
+}
+
+/// Refuses a daemon run by another user, which would see every key typed.
+fn owned(stream: UnixStream) -> io::Result<UnixStream> {
+    if peer_uid(&stream)? != unsafe { libc::getuid() } {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "the daemon's socket belongs to another user",
+        ));
+    }
+    Ok(stream)
+}
+
+/// Creates `dir` for the current user alone, or checks that an existing one
+/// is theirs and closed to everyone else.
+fn private_dir(dir: &Path) -> io::Result<()> {
+    match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+        result => result?,
+    }
+
+    let metadata = std::fs::symlink_metadata(dir)?;
+    if !metadata.is_dir()
+        || metadata.uid() != unsafe { libc::getuid() }
+        || metadata.mode() & 0o077 != 0
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} isn't a directory private to you", dir.display()),
+        ));
+    }
+    Ok(())
+}
+
+/// The user on the other end of a connection.
+#[cfg(target_os = "linux")]
+fn peer_uid(stream: &UnixStream) -> io::Result<u32> {
+    let mut cred = libc::ucred {
+        pid: 0,
+        uid: 0,
+        gid: 0,
+    };
+    let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    let result = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            &mut cred as *mut libc::ucred as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(cred.uid)
+}
+
+/// The user on the other end of a connection.
+#[cfg(not(target_os = "linux"))]
+fn peer_uid(stream: &UnixStream) -> io::Result<u32> {
+    let (mut uid, mut gid) = (0, 0);
+    if unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(uid)
+}
+
+/// Lists the sessions kept alive by the daemon.
+pub fn sessions() -> io::Result<Vec<SessionInfo>> {
+    let mut stream = connect()?;
+    Request::List.write_to(&mut stream)?;
+    match Reply::read_from(&mut stream)? {
+        Reply::Sessions(sessions) => Ok(sessions),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unexpected reply",
+        )),
+    }
+}
+
+struct Session {
+    workspace: u32,
+    pid: u32,
+    term: Arc<FairMutex<Term<SessionListener>>>,
+    /// Mouse encodings the terminal doesn't track, kept for snapshots.
+    mouse_encodings: Arc<MouseEncodings>,
+    pty: Mutex<Option<Pty>>,
+    writer: Arc<Mutex<File>>,
+    /// Queues of replies for the attached clients, each sent by a thread
+    /// of its own so a stalled client can't hold up the session.
+    clients: Mutex<Vec<Client>>,
+}
+
+struct Client {
+    id: u64,
+    queue: SyncSender<Reply>,
+    /// Set when the queue overflowed, so output is skipped until the client
+    /// is sent a snapshot of the screen.
+    behind: Arc<AtomicBool>,
+}
+
+impl Session {
+    fn spawn(
+        id: u64,
+        workspace: u32,
+        options: tty::Options,
+        size: WindowSize,
+        config: &term::Config,
+    ) -> io::Result<Self> {
+        let pty = tty::new(&options, size, id)?;
+        let pid = pty.child().id();
+        let file = pty.file().try_clone()?;
+        set_blocking(&file);
+        let writer = Arc::new(Mutex::new(file));
+        let term = Term::new(
+            config.clone(),
+            &TermSize::new(size.num_cols as usize, size.num_lines as usize),
+            SessionListener(writer.clone()),
+        );
+
+        Ok(Self {
+            workspace,
+            pid,
+            term: Arc::new(FairMutex::new(term)),
+            mouse_encodings: Arc::default(),
+            pty: Mutex::new(Some(pty)),
+            writer,
+            clients: Mutex::new(vec![]),
+        })
+    }
+
+    fn resize(&self, size: WindowSize) {
+        if let Some(pty) = self.pty.lock().unwrap().as_mut() {
+            pty.on_resize(size);
+        }
+        self.term.lock().resize(TermSize::new(
+            size.num_cols as usize,
+            size.num_lines as usize,
+        ));
+    }
+
+    /// Queues output for every client that is keeping up. Called with the
+    /// terminal locked, which is also how clients that fell behind resync.
+    fn broadcast(&self, reply: &Reply) {
+        self.clients.lock().unwrap().retain(|client| {
+            if client.behind.load(Ordering::Acquire) {
+                return true;
+            }
+            match client.queue.try_send(reply.clone()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    client.behind.store(true, Ordering::Release);
+                    true
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            }
+        });
+    }
+
+    /// Tells every client the shell exited, waiting on the ones that are
+    /// behind since they would otherwise never hear of it.
+    fn exit(&self) {
+        let clients = std::mem::take(&mut *self.clients.lock().unwrap());
+        for client in clients {
+            let _ = client.queue.send(Reply::Exit);
+        }
+    }
+
+    /// Attaches a client and queues the current screen for it. The terminal
+    /// stays locked meanwhile so no output is lost or sent twice.
+    fn attach(&self, client: u64, mut stream: UnixStream) -> io::Result<()> {
+        stream.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT))?;
+        let (queue, replies) = mpsc::sync_channel(CLIENT_QUEUE);
+        let behind = Arc::new(AtomicBool::new(false));
+        {
+            let term = self.term.lock();
+            let _ = queue.try_send(Reply::Output(snapshot::snapshot(
+                &term,
+                &self.mouse_encodings,
+            )));
+            self.clients.lock().unwrap().push(Client {
+                id: client,
+                queue,
+                behind: behind.clone(),
+            });
+        }
+
+        let term = self.term.clone();
+        let encodings = self.mouse_encodings.clone();
+        thread::spawn(move || {
+            if send_replies(&mut stream, replies, &term, &encodings, &behind).is_err() {
+                let _ = stream.shutdown(std::net::Shutdown::Both);
+            }
+        });
+        Ok(())
+    }
+
+    fn detach(&self, client: u64) {
+        self.clients
+            .lock()
+            .unwrap()
+            .retain(|queued| queued.id != client);
+    }
+}
+
+/// Writes a client's queued replies until it detaches. Once the queue of a
+/// client that fell behind runs dry, it is sent the current screen and picks
+/// up the output from there.
+fn send_replies(
+    stream: &mut UnixStream,
+    replies: Receiver<Reply>,
+    term: &FairMutex<Term<SessionListener>>,
+    encodings: &MouseEncodings,
+    behind: &AtomicBool,
+) -> io::Result<()> {
+    loop {
+        let reply = match replies.try_recv() {
+            Ok(reply) => reply,
+            Err(TryRecvError::Empty) if behind.load(Ordering::Acquire) => {
+                let screen = {
+                    let term = term.lock();
+                    behind.store(false, Ordering::Release);
+                    snapshot::snapshot(&term, encodings)
+                };
+                Reply::Output(screen)
+            }
+            Err(TryRecvError::Empty) => match replies.recv() {
+                Ok(reply) => reply,
+                Err(_) => return Ok(()),
+            },
+            Err(TryRecvError::Disconnected) => return Ok(()),
+        };
+        reply.write_to(stream)?;
+    }
+}
+
+/// Reads the session's PTY until its child exits, feeding the output to the
+/// session's terminal and every attached client.
+fn pump(id: u64, session: Arc<Session>, sessions: Sessions) {
+    let mut reader = session.writer.lock().unwrap().try_clone().unwrap();
+    let mut processor: Processor = Processor::new();
+    let mut scanner = Scanner::new(session.mouse_encodings.clone());
+    let mut buf = [0; 0x10000];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(len) => {
+                // Only queued under the lock, the clients' threads send it
+                let mut term = session.term.lock();
+                for byte in &buf[..len] {
+                    processor.advance(&mut *term, *byte);
+                }
+                scanner.advance(&buf[..len]);
+                session.broadcast(&Reply::Output(buf[..len].to_vec()));
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        }
+    }
+
+    sessions.lock().unwrap().remove(&id);
+    session.pty.lock().unwrap().take();
+    session.exit();
+}
+
+fn serve(
+    stream: UnixStream,
+    sessions: &Sessions,
+    next_id: &AtomicU64,
+    config: &term::Config,
+) -> io::Result<()> {
+    let client = next_id.fetch_add(1, Ordering::Relaxed);
+    let mut reader = stream.try_clone()?;
+    let mut attached: Option<Arc<Session>> = None;
+
+    let result = loop {
+        let request = match Request::read_from(&mut reader) {
+            Ok(request) => request,
+            Err(err) => break Err(err),
+        };
+
+        match request {
+            Request::List => {
+                let list = sessions
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|(id, session)| SessionInfo {
+                        id: *id,
+                        workspace: session.workspace,
+                        attached: !session.clients.lock().unwrap().is_empty(),
+                    })
+                    .collect();
+                Reply::Sessions(list).write_to(&mut &stream)?;
+            }
+            Request::Create {
+                workspace,
+                size,
+                program,
+                args,
+                cwd,
+                env,
+            } => {
+                let options = tty::Options {
+                    shell: Some(tty::Shell::new(program, args)),
+                    working_directory: cwd.map(PathBuf::from),
+                    env: env.into_iter().collect(),
+                    ..tty::Options::default()
+                };
+                let id = next_id.fetch_add(1, Ordering::Relaxed);
+                let session = Arc::new(Session::spawn(id, workspace, options, size, config)?);
+                sessions.lock().unwrap().insert(id, session.clone());
+                Reply::Attached {
+                    session: id,
+                    pid: session.pid,
+                }
+                .write_to(&mut &stream)?;
+                session.attach(client, stream.try_clone()?)?;
+
+                let (pumped, sessions) = (session.clone(), sessions.clone());
+                thread::spawn(move || pump(id, pumped, sessions));
+                attached = Some(session);
+            }
+            Request::Attach { session: id, size } => {
+                let session = sessions.lock().unwrap().get(&id).cloned();
+                match session {
+                    Some(session) => {
+                        session.resize(size);
+                        Reply::Attached {
+                            session: id,
+                            pid: session.pid,
+                        }
+                        .write_to(&mut &stream)?;
+                        session.attach(client, stream.try_clone()?)?;
+                        attached = Some(session);
+                    }
+                    None => Reply::Exit.write_to(&mut &stream)?,
+                }
+            }
+            Request::Input(bytes) => {
+                if let Some(session) = &attached {
+                    session.writer.lock().unwrap().write_all(&bytes)?;
+                }
+            }
+            Request::Resize(size) => {
+                if let Some(session) = &attached {
+                    session.resize(size);
+                }
+            }
+            Request::Kill => {
+                if let Some(session) = &attached {
+                    session.pty.lock().unwrap().take();
+                }
+            }
+        }
+    };
+
+    if let Some(session) = attached {
+        session.detach(client);
+    }
+    result
+}
+
+/// Answers the terminal's own replies, like cursor position reports, on the PTY.
+struct SessionListener(Arc<Mutex<File>>);
+
+impl EventListener for SessionListener {
+    fn send_event(&self, event: Event) {
+        if let Event::PtyWrite(text) = event {
+            let _ = self.0.lock().unwrap().write_all(text.as_bytes());
+        }
+    }
+}
+
+/// `tty::new` leaves the master non-blocking for alacritty's event loop, but
+/// sessions are pumped from a thread of their own.
+fn set_blocking(file: &File) {
+    unsafe {
+        let fd = file.as_raw_fd();
+        let flags = libc::fcntl(fd, libc::F_GETFL, 0);
+        libc::fcntl(fd, libc::F_SETFL, flags & !libc::O_NONBLOCK);
+    }
+}
@@ -0,0 +1,495 @@
+//! Frames exchanged between `frostty --daemon` and the windows attached to it.
+//!
+//! Every frame is a one byte tag, a little endian `u32` body length and the
+//! body itself. Each attached terminal uses its own connection.
+//!
+//! Bodies are at most [`MAX_BODY`] long, so input and output larger than
+//! that go out as several frames.
+
+use alacritty_terminal::event::WindowSize;
+use std::env;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+/// The longest body either side accepts, which keeps a peer from making
+/// the other allocate whatever it likes.
+pub const MAX_BODY: usize = 1 << 20;
+
+#[derive(Debug, Clone)]
+pub enum Request {
+    List,
+    Create {
+        workspace: u32,
+        size: WindowSize,
+        program: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        env: Vec<(String, String)>,
+    },
+    Attach {
+        session: u64,
+        size: WindowSize,
+    },
+    Input(Vec<u8>),
+    Resize(WindowSize),
+    Kill,
+}
+
+#[derive(Debug, Clone)]
+pub enum Reply {
+    Sessions(Vec<SessionInfo>),
+    /// The session attached to, with the pid of its shell.
+    Attached {
+        session: u64,
+        pid: u32,
+    },
+    Output(Vec<u8>),
+    Exit,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SessionInfo {
+    pub id: u64,
+    pub workspace: u32,
+    pub attached: bool,
+}
+
+/// Directory holding the daemon's socket, which only the current user may
+/// enter.
+pub fn socket_dir() -> PathBuf {
+    match env::var("XDG_RUNTIME_DIR") {
+        Ok(dir) => PathBuf::from(dir).join("frostty"),
+        Err(_) => env::temp_dir().join(format!("frostty-{}", unsafe { libc::getuid() })),
+    }
+}
+
+/// Location of the daemon's socket.
+pub fn socket_path() -> PathBuf {
+    socket_dir().join("frostty.sock")
+}
+
+impl Request {
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        if let Request::Input(bytes) = self
+            && bytes.len() > MAX_BODY
+        {
+            for chunk in bytes.chunks(MAX_BODY) {
+                Request::Input(chunk.to_vec()).write_to(writer)?;
+            }
+            return Ok(());
+        }
+
+        let mut body = Body::default();
+        let tag = match self {
+            Request::List => 0,
+            Request::Create {
+                workspace,
+                size,
+                program,
+                args,
+                cwd,
+                env,
+            } => {
+                body.put_u32(*workspace);
+                body.put_size(*size);
+                body.put_str(program);
+                body.put_u32(args.len() as u32);
+                for arg in args {
+                    body.put_str(arg);
+                }
+                body.put_str(cwd.as_deref().unwrap_or_default());
+                body.put_u32(env.len() as u32);
+                for (key, value) in env {
+                    body.put_str(key);
+                    body.put_str(value);
+                }
+                1
+            }
+            Request::Attach { session, size } => {
+                body.put_u64(*session);
+                body.put_size(*size);
+                2
+            }
+            Request::Input(bytes) => {
+                body.put_bytes(bytes);
+                3
+            }
+            Request::Resize(size) => {
+                body.put_size(*size);
+                4
+            }
+            Request::Kill => 5,
+        };
+
+        write_frame(writer, tag, &body.0)
+    }
+
+    pub fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let (tag, body) = read_frame(reader)?;
+        let mut body = Cursor(&body);
+        let request = match tag {
+            0 => Request::List,
+            1 => {
+                let workspace = body.u32()?;
+                let size = body.size()?;
+                let program = body.string()?;
+                let args = (0..body.u32()?)
+                    .map(|_| body.string())
+                    .collect::<io::Result<_>>()?;
+                let cwd = Some(body.string()?).filter(|cwd| !cwd.is_empty());
+                let env = (0..body.u32()?)
+                    .map(|_| Ok((body.string()?, body.string()?)))
+                    .collect::<io::Result<_>>()?;
+                Request::Create {
+                    workspace,
+                    size,
+                    program,
+                    args,
+                    cwd,
+                    env,
+                }
+            }
+            2 => Request::Attach {
+                session: body.u64()?,
+                size: body.size()?,
+            },
+            3 => Request::Input(body.rest()),
+            4 => Request::Resize(body.size()?),
+            5 => Request::Kill,
+            _ => return Err(invalid("unknown request")),
+        };
+
+        Ok(request)
+    }
+}
+
+impl Reply {
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        if let Reply::Output(bytes) = self
+            && bytes.len() > MAX_BODY
+        {
+            for chunk in bytes.chunks(MAX_BODY) {
+                Reply::Output(chunk.to_vec()).write_to(writer)?;
+            }
+            return Ok(());
+        }
+
+        let mut body = Body::default();
+        let tag = match self {
+            Reply::Sessions(sessions) => {
+                body.put_u32(sessions.len() as u32);
+                for session in sessions {
+                    body.put_u64(session.id);
+                    body.put_u32(session.workspace);
+                    body.0.push(session.attached as u8);
+                }
+                0
+            }
+            Reply::Attached { session, pid } => {
+                body.put_u64(*session);
+                body.put_u32(*pid);
+                1
+            }
+            Reply::Output(bytes) => {
+                body.put_bytes(bytes);
+                2
+            }
+            Reply::Exit => 3,
+        };
+
+        write_frame(writer, tag, &body.0)
+    }
+
+    pub fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let (tag, body) = read_frame(reader)?;
+        let mut body = Cursor(&body);
+        let reply = match tag {
+            0 => Reply::Sessions(
+                (0..body.u32()?)
+                    .map(|_| {
+                        Ok(SessionInfo {
+                            id: body.u64()?,
+                            workspace: body.u32()?,
+                            attached: body.take(1)?[0] != 0,
+                        })
+                    })
+                    .collect::<io::Result<_>>()?,
+            ),
+            1 => Reply::Attached {
+                session: body.u64()?,
+                pid: body.u32()?,
+            },
+            2 => Reply::Output(body.rest()),
+            3 => Reply::Exit,
+            _ => return Err(invalid("unknown reply")),
+        };
+
+        Ok(reply)
+    }
+}
+
+fn write_frame(writer: &mut impl Write, tag: u8, body: &[u8]) -> io::Result<()> {
+    if body.len() > MAX_BODY {
+        return Err(invalid("frame too long"));
+    }
+    let mut frame = Vec::with_capacity(body.len() + 5);
+    frame.push(tag);
+    frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    frame.extend_from_slice(body);
+    writer.write_all(&frame)
+}
+
+fn read_frame(reader: &mut impl Read) -> io::Result<(u8, Vec<u8>)> {
+    let mut header = [0; 5];
+    reader.read_exact(&mut header)?;
+    let len = u32::from_le_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    if len > MAX_BODY {
+        return Err(invalid("frame too long"));
+    }
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body)?;
+    Ok((header[0], body))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[derive(Default)]
+struct Body(Vec<u8>);
+
+impl Body {
+    fn put_u16(&mut self, value: u16) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u64(&mut self, value: u64) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_str(&mut self, value: &str) {
+        self.put_u32(value.len() as u32);
+        self.0.extend_from_slice(value.as_bytes());
+    }
+
+    fn put_bytes(&mut self, value: &[u8]) {
+        self.0.extend_from_slice(value);
+    }
+
+    fn put_size(&mut self, size: WindowSize) {
+        self.put_u16(size.num_lines);
+        self.put_u16(size.num_cols);
+        self.put_u16(size.cell_width);
+        self.put_u16(size.cell_height);
+    }
+}
+
+struct Cursor<'a>(&'a [u8]);
+
+impl Cursor<'_> {
+    fn take(&mut self, len: usize) -> io::Result<&[u8]> {
+        if self.0.len() < len {
+            return Err(invalid("truncated frame"));
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| invalid("invalid utf-8"))
+    }
+
+    fn size(&mut self) -> io::Result<WindowSize> {
+        Ok(WindowSize {
+            num_lines: self.u16()?,
+            num_cols: self.u16()?,
+            cell_width: self.u16()?,
+            cell_height: self.u16()?,
+        })
+    }
+
+    fn rest(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.0).to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: WindowSize = WindowSize {
+        num_lines: 24,
+        num_cols: 80,
+        cell_width: 9,
+        cell_height: 18,
+    };
+
+    fn dimensions(size: WindowSize) -> [u16; 4] {
+        [
+            size.num_lines,
+            size.num_cols,
+            size.cell_width,
+            size.cell_height,
+        ]
+    }
+
+    fn replies(bytes: &[u8]) -> Vec<Reply> {
+        let mut reader = bytes;
+        let mut replies = vec![];
+        while !reader.is_empty() {
+            replies.push(Reply::read_from(&mut reader).unwrap());
+        }
+        replies
+    }
+
+    #[test]
+    fn requests_round_trip() {
+        let create = Request::Create {
+            workspace: 3,
+            size: SIZE,
+            program: "zsh".to_string(),
+            args: vec!["-l".to_string(), String::new()],
+            cwd: Some("/tmp".to_string()),
+            env: vec![("TERM".to_string(), "xterm-256color".to_string())],
+        };
+        let mut bytes = vec![];
+        create.write_to(&mut bytes).unwrap();
+        Request::Attach {
+            session: u64::MAX,
+            size: SIZE,
+        }
+        .write_to(&mut bytes)
+        .unwrap();
+        Request::Kill.write_to(&mut bytes).unwrap();
+
+        let mut reader = bytes.as_slice();
+        match Request::read_from(&mut reader).unwrap() {
+            Request::Create {
+                workspace,
+                size,
+                program,
+                args,
+                cwd,
+                env,
+            } => {
+                assert_eq!((workspace, program.as_str()), (3, "zsh"));
+                assert_eq!(dimensions(size), dimensions(SIZE));
+                assert_eq!(args, ["-l", ""]);
+                assert_eq!(cwd.as_deref(), Some("/tmp"));
+                assert_eq!(env, [("TERM".to_string(), "xterm-256color".to_string())]);
+            }
+            other => panic!("{other:?}"),
+        }
+        assert!(matches!(
+            Request::read_from(&mut reader).unwrap(),
+            Request::Attach { session: u64::MAX, size } if dimensions(size) == dimensions(SIZE)
+        ));
+        assert!(matches!(
+            Request::read_from(&mut reader).unwrap(),
+            Request::Kill
+        ));
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn replies_round_trip() {
+        let mut bytes = vec![];
+        let sessions = vec![SessionInfo {
+            id: 7,
+            workspace: 2,
+            attached: true,
+        }];
+        Reply::Sessions(sessions).write_to(&mut bytes).unwrap();
+        Reply::Attached {
+            session: 7,
+            pid: 42,
+        }
+        .write_to(&mut bytes)
+        .unwrap();
+        Reply::Exit.write_to(&mut bytes).unwrap();
+
+        match replies(&bytes).as_slice() {
+            [
+                Reply::Sessions(sessions),
+                Reply::Attached {
+                    session: 7,
+                    pid: 42,
+                },
+                Reply::Exit,
+            ] => {
+                let session = sessions[0];
+                assert_eq!((session.id, session.workspace), (7, 2));
+                assert!(session.attached);
+            }
+            other => panic!("{other:?}"),
+        }
+    }
+
+    #[test]
+    fn long_output_is_split_into_frames() {
+        let output: Vec<u8> = (0..MAX_BODY * 2 + 10).map(|i| i as u8).collect();
+        let mut bytes = vec![];
+        Reply::Output(output.clone()).write_to(&mut bytes).unwrap();
+
+        let chunks: Vec<Vec<u8>> = replies(&bytes)
+            .into_iter()
+            .map(|reply| match reply {
+                Reply::Output(chunk) => chunk,
+                other => panic!("{other:?}"),
+            })
+            .collect();
+        let lengths: Vec<usize> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(lengths, [MAX_BODY, MAX_BODY, 10]);
+        assert_eq!(chunks.concat(), output);
+    }
+
+    #[test]
+    fn long_input_is_split_into_frames() {
+        let mut bytes = vec![];
+        Request::Input(vec![b'x'; MAX_BODY + 1])
+            .write_to(&mut bytes)
+            .unwrap();
+        let mut reader = bytes.as_slice();
+        let mut lengths = vec![];
+        while !reader.is_empty() {
+            match Request::read_from(&mut reader).unwrap() {
+                Request::Input(chunk) => lengths.push(chunk.len()),
+                other => panic!("{other:?}"),
+            }
+        }
+        assert_eq!(lengths, [MAX_BODY, 1]);
+    }
+
+    #[test]
+    fn oversized_and_truncated_frames_are_rejected() {
+        let mut frame = vec![2];
+        frame.extend_from_slice(&(MAX_BODY as u32 + 1).to_le_bytes());
+        let error = Reply::read_from(&mut frame.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        // An attach request missing its window size.
+        let mut frame = vec![2];
+        frame.extend_from_slice(&8u32.to_le_bytes());
+        frame.extend_from_slice(&7u64.to_le_bytes());
+        let error = Request::read_from(&mut frame.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
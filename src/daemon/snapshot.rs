@@ -0,0 +1,248 @@
+use crate::terminal::MouseEncodings;
+use alacritty_terminal::event::EventListener;
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::Column;
+use alacritty_terminal::term::cell::{Cell, Flags};
+use alacritty_terminal::term::{Term, TermMode};
+use alacritty_terminal::vte::ansi::{Color, CursorShape, NamedColor};
+use std::fmt::Write;
+
+/// Private modes restored on a freshly attached terminal, with their DECSET number.
+const PRIVATE_MODES: [(TermMode, u16); 11] = [
+    (TermMode::APP_CURSOR, 1),
+    (TermMode::ORIGIN, 6),
+    (TermMode::MOUSE_REPORT_CLICK, 1000),
+    (TermMode::MOUSE_DRAG, 1002),
+    (TermMode::MOUSE_MOTION, 1003),
+    (TermMode::FOCUS_IN_OUT, 1004),
+    (TermMode::UTF8_MOUSE, 1005),
+    (TermMode::SGR_MOUSE, 1006),
+    (TermMode::ALTERNATE_SCROLL, 1007),
+    (TermMode::BRACKETED_PASTE, 2004),
+    (TermMode::SHOW_CURSOR, 25),
+];
+
+/// Offset of the kitty keyboard flags within [`TermMode`].
+const KITTY_KEYBOARD_SHIFT: u32 = TermMode::DISAMBIGUATE_ESC_CODES.bits().trailing_zeros();
+
+/// Serializes the scrollback, screen, cursor and modes of `term`, along with
+/// the mouse `encodings` it doesn't track itself, into escape sequences that
+/// rebuild the same state on a blank terminal of equal size.
+pub fn snapshot<T: EventListener>(term: &Term<T>, encodings: &MouseEncodings) -> Vec<u8> {
+    let grid = term.grid();
+    let mode = *term.mode();
+    let mut out = String::from("\x1bc");
+
+    if mode.contains(TermMode::ALT_SCREEN) {
+        out.push_str("\x1b[?1049h");
+    }
+
+    let mut pen = Pen::default();
+    let last_line = grid.bottommost_line();
+    let mut line = grid.topmost_line();
+    while line <= last_line {
+        let row = &grid[line];
+        let wrapped = row[grid.last_column()].flags.contains(Flags::WRAPLINE);
+        let len = if wrapped {
+            grid.columns()
+        } else {
+            (0..grid.columns())
+                .rposition(|column| !is_blank(&row[Column(column)]))
+                .map_or(0, |column| column + 1)
+        };
+
+        for column in 0..len {
+            let cell = &row[Column(column)];
+            if cell
+                .flags
+                .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+            {
+                continue;
+            }
+            pen.update(cell, &mut out);
+            out.push(cell.c);
+            if let Some(zerowidth) = cell.zerowidth() {
+                out.extend(zerowidth);
+            }
+        }
+
+        if !wrapped && line != last_line {
+            if pen != Pen::default() {
+                out.push_str("\x1b[0m");
+                pen = Pen::default();
+            }
+            out.push_str("\r\n");
+        }
+        line += 1;
+    }
+
+    out.push_str("\x1b[0m");
+    for (flag, number) in PRIVATE_MODES {
+        let set = if mode.contains(flag) { 'h' } else { 'l' };
+        let _ = write!(out, "\x1b[?{number}{set}");
+    }
+    for (enabled, number) in [(encodings.urxvt(), 1015), (encodings.sgr_pixels(), 1016)] {
+        let set = if enabled { 'h' } else { 'l' };
+        let _ = write!(out, "\x1b[?{number}{set}");
+    }
+    if !mode.contains(TermMode::LINE_WRAP) {
+        out.push_str("\x1b[?7l");
+    }
+    if mode.contains(TermMode::APP_KEYPAD) {
+        out.push_str("\x1b=");
+    }
+    if mode.contains(TermMode::INSERT) {
+        out.push_str("\x1b[4h");
+    }
+    if mode.contains(TermMode::LINE_FEED_NEW_LINE) {
+        out.push_str("\x1b[20h");
+    }
+    // Only the active kitty keyboard flags can be read, so they're pushed as
+    // the single entry of the screen's stack
+    let keyboard = (mode & TermMode::KITTY_KEYBOARD_PROTOCOL).bits() >> KITTY_KEYBOARD_SHIFT;
+    if keyboard != 0 {
+        let _ = write!(out, "\x1b[>{keyboard}u");
+    }
+
+    let style = term.cursor_style();
+    let shape = match style.shape {
+        CursorShape::Underline => 3,
+        CursorShape::Beam => 5,
+        _ => 1,
+    };
+    let _ = write!(out, "\x1b[{} q", shape + !style.blinking as u8);
+
+    let cursor = grid.cursor.point;
+    let _ = write!(out, "\x1b[{};{}H", cursor.line.0 + 1, cursor.column.0 + 1);
+
+    out.into_bytes()
+}
+
+fn is_blank(cell: &Cell) -> bool {
+    cell.c == ' '
+        && cell.bg == Color::Named(NamedColor::Background)
+        && !cell
+            .flags
+            .intersects(Flags::INVERSE | Flags::ALL_UNDERLINES)
+}
+
+/// The graphic rendition last written to the output, so unchanged runs of
+/// cells don't repeat their SGR sequence.
+#[derive(Default, PartialEq)]
+struct Pen {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    flags: Option<Flags>,
+}
+
+impl Pen {
+    fn update(&mut self, cell: &Cell, out: &mut String) {
+        let flags = cell.flags
+            & (Flags::BOLD
+                | Flags::DIM
+                | Flags::ITALIC
+                | Flags::ALL_UNDERLINES
+                | Flags::INVERSE
+                | Flags::HIDDEN
+                | Flags::STRIKEOUT);
+        let pen = Pen {
+            fg: Some(cell.fg),
+            bg: Some(cell.bg),
+            flags: Some(flags),
+        };
+        if *self == pen {
+            return;
+        }
+
+        out.push_str("\x1b[0");
+        for (flag, sgr) in [
+            (Flags::BOLD, "1"),
+            (Flags::DIM, "2"),
+            (Flags::ITALIC, "3"),
+            (Flags::UNDERLINE, "4"),
+            (Flags::DOUBLE_UNDERLINE, "4:2"),
+            (Flags::UNDERCURL, "4:3"),
+            (Flags::DOTTED_UNDERLINE, "4:4"),
+            (Flags::DASHED_UNDERLINE, "4:5"),
+            (Flags::INVERSE, "7"),
+            (Flags::HIDDEN, "8"),
+            (Flags::STRIKEOUT, "9"),
+        ] {
+            if flags.contains(flag) {
+                out.push(';');
+                out.push_str(sgr);
+            }
+        }
+        push_color(out, cell.fg, 30);
+        push_color(out, cell.bg, 40);
+        out.push('m');
+
+        *self = pen;
+    }
+}
+
+fn push_color(out: &mut String, color: Color, base: u8) {
+    let _ = match color {
+        Color::Named(named) => match named as usize {
+            index @ 0..8 => write!(out, ";{}", base as usize + index),
+            index @ 8..16 => write!(out, ";{}", base as usize + 60 + index - 8),
+            _ => Ok(()),
+        },
+        Color::Indexed(index) => write!(out, ";{};5;{}", base + 8, index),
+        Color::Spec(rgb) => write!(out, ";{};2;{};{};{}", base + 8, rgb.r, rgb.g, rgb.b),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::Scanner;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::term::{Config, test::TermSize};
+    use alacritty_terminal::vte::ansi::Processor;
+    use std::sync::Arc;
+
+    fn replay(bytes: &[u8]) -> Term<VoidListener> {
+        let config = Config {
+            kitty_keyboard: true,
+            ..Config::default()
+        };
+        let mut term = Term::new(config, &TermSize::new(20, 5), VoidListener);
+        let mut processor: Processor = Processor::new();
+        for byte in bytes {
+            processor.advance(&mut term, *byte);
+        }
+        term
+    }
+
+    #[test]
+    fn kitty_keyboard_flags_survive_a_snapshot() {
+        for setup in ["\x1b[>5u", "\x1b[?1049h\x1b[>31u"] {
+            let term = replay(setup.as_bytes());
+            let restored = replay(&snapshot(&term, &MouseEncodings::default()));
+            assert_eq!(
+                *restored.mode() & TermMode::KITTY_KEYBOARD_PROTOCOL,
+                *term.mode() & TermMode::KITTY_KEYBOARD_PROTOCOL,
+                "{setup:?}",
+            );
+        }
+    }
+
+    #[test]
+    fn mouse_encodings_survive_a_snapshot() {
+        for setup in [
+            "\x1b[?1015h",
+            "\x1b[?1016h",
+            "\x1b[?1015h\x1b[?1016h\x1b[?1015l",
+        ] {
+            let encodings = Arc::new(MouseEncodings::default());
+            Scanner::new(encodings.clone()).advance(setup.as_bytes());
+            let bytes = snapshot(&replay(setup.as_bytes()), &encodings);
+
+            let restored = Arc::new(MouseEncodings::default());
+            Scanner::new(restored.clone()).advance(&bytes);
+            assert_eq!(restored.urxvt(), encodings.urxvt(), "{setup:?}");
+            assert_eq!(restored.sgr_pixels(), encodings.sgr_pixels(), "{setup:?}");
+        }
+    }
+}
@@ -0,0 +1,403 @@
+use iced::time::Instant;
+use iced::widget::{column, container, horizontal_space, mouse_area, pane_grid, row};
+use iced::{Element, Fill, Padding, Point, Rectangle, Size, Task, Theme, mouse, window};
+
+use crate::style::Styles;
+use crate::terminal::TerminalView;
+use crate::workspace::Pane;
+use crate::{Frostty, Message, config, layout, view_terminal};
+
+/// A terminal drawn above the tiled panes at a position of its own, relative
+/// to the window.
+#[derive(Clone)]
+pub struct Float {
+    pub pane: Pane,
+    pub bounds: Rectangle,
+}
+
+/// A floating terminal being moved or resized with the mouse.
+#[derive(Debug, Clone, Copy)]
+pub enum GrabKind {
+    Move,
+    Resize,
+}
+
+impl GrabKind {
+    /// `bounds` moved or resized by the given distance.
+    fn apply(self, mut bounds: Rectangle, dx: f32, dy: f32) -> Rectangle {
+        match self {
+            GrabKind::Move => {
+                bounds.x += dx;
+                bounds.y += dy;
+            }
+            GrabKind::Resize => {
+                bounds.width += dx;
+                bounds.height += dy;
+            }
+        }
+        bounds
+    }
+}
+
+pub struct Grab {
+    window: window::Id,
+    id: usize,
+    kind: GrabKind,
+    /// Cursor position when the grab started, known from the first movement.
+    origin: Option<Point>,
+    bounds: Rectangle,
+}
+
+impl Frostty {
+    /// Floats the focused pane above the grid, or tiles the focused floating
+    /// terminal again.
+    pub fn toggle_float(&mut self) -> Task<Message> {
+        if let Some(id) = self.active().floating_focus {
+            // Without a pane to split, a manual layout leaves it floating.
+            if Some(id) != self.scratchpad && self.active().accepts_pane() {
+                let float = self.remove_float(self.window, self.current().workspace, id);
+                match self.add_pane(float.pane.clone()) {
+                    Some(pane) => return self.focus_pane(pane),
+                    None => self.kill_pane(float.pane),
+                }
+            }
+        } else if let Some(pane) = self.active().focus
+            && let Some(state) = self.active().panes.get(pane)
+            && !state.is_pinned
+            && (state.tabs.len() > 1 || self.active().panes.len() > 1)
+        {
+            let (window, index) = (self.window, self.current().workspace);
+            let (floated, task) = if state.tabs.len() > 1 {
+                // Only the visible tab floats, leaving the rest of the stack.
+                let id = state.id;
+                self.active_mut().remove_tab(pane, id);
+                (Pane::new(id), self.focus_pane(pane))
+            } else {
+                self.take_pane(window, index, pane)
+            };
+            let id = floated.id;
+            let bounds = self.float_bounds(0.6, 0.6, false);
+            self.active_mut().floating.push(Float {
+                pane: floated,
+                bounds,
+            });
+            return Task::batch([task, self.focus_float(id)]);
+        }
+        Task::none()
+    }
+
+    /// Shows the scratchpad in the focused workspace, starting it if needed, or
+    /// hides it when it has focus.
+    pub fn toggle_scratchpad(&mut self) -> Task<Message> {
+        let index = self.current().workspace;
+        if let Some(id) = self.scratchpad {
+            if self.active().floating_focus == Some(id) {
+                // Hiding keeps the terminal and its shell alive.
+                self.remove_float(self.window, index, id);
+                return self.refocus();
+            }
+            let float = match self.locate_float(id) {
+                Some((window, index)) => self.remove_float(window, index, id),
+                None => Float {
+                    pane: Pane::new(id),
+                    bounds: self.scratchpad_bounds(),
+                },
+            };
+            self.active_mut().floating.push(float);
+            return self.focus_float(id);
+        }
+
+        let settings = match self
+            .config
+            .as_ref()
+            .and_then(|config| config.scratchpad.as_ref())
+            .and_then(|scratchpad| scratchpad.command.clone())
+        {
+            Some(command) => layout::backend_settings(
+                &config::LayoutPane {
+                    command: Some(command),
+                    cwd: None,
+                    env: None,
+                    focus: None,
+                },
+                &self.term_settings.backend,
+            ),
+            None => self.term_settings.backend.clone(),
+        };
+        let id = self.spawn_terminal(settings, index);
+        self.scratchpad = Some(id);
+        let bounds = self.scratchpad_bounds();
+        self.active_mut().floating.push(Float {
+            pane: Pane::new(id),
+            bounds,
+        });
+        self.focus_float(id)
+    }
+
+    /// Starts moving or resizing the floating terminal `id` with the mouse.
+    pub fn grab_float(&mut self, window: window::Id, id: usize, kind: GrabKind) -> Task<Message> {
+        if let Some(float) = self.windows.get(&window).and_then(|state| {
+            state
+                .active()
+                .floating
+                .iter()
+                .find(|float| float.pane.id == id)
+        }) {
+            self.grab = Some(Grab {
+                window,
+                id,
+                kind,
+                origin: None,
+                bounds: float.bounds,
+            });
+            self.window = window;
+            return self.focus_float(id);
+        }
+        Task::none()
+    }
+
+    /// Follows the cursor with the grabbed floating terminal.
+    pub fn move_grab(&mut self, position: Point) {
+        if let Some(grab) = self.grab.as_mut() {
+            let origin = *grab.origin.get_or_insert(position);
+            let delta = position - origin;
+            let bounds = grab.kind.apply(grab.bounds, delta.x, delta.y);
+            let (window, id) = (grab.window, grab.id);
+            self.set_float_bounds(window, id, bounds);
+        }
+    }
+
+    /// Focuses the floating terminal `id` of the focused workspace, raising it
+    /// above the others.
+    pub fn focus_float(&mut self, id: usize) -> Task<Message> {
+        let workspace = self.active_mut();
+        if let Some(position) = workspace
+            .floating
+            .iter()
+            .position(|float| float.pane.id == id)
+        {
+            let mut float = workspace.floating.remove(position);
+            float.pane.rang = false;
+            workspace.floating.push(float);
+            workspace.floating_focus = Some(id);
+        }
+        TerminalView::focus(self.terminals.get(&(id as u64)).unwrap().widget_id())
+    }
+
+    pub fn locate_float(&self, id: usize) -> Option<(window::Id, usize)> {
+        self.windows.iter().find_map(|(window, state)| {
+            state.workspaces.iter().find_map(|(index, workspace)| {
+                workspace
+                    .floating
+                    .iter()
+                    .any(|float| float.pane.id == id)
+                    .then_some((*window, *index))
+            })
+        })
+    }
+
+    pub fn remove_float(&mut self, window: window::Id, index: usize, id: usize) -> Float {
+        let workspace = self
+            .windows
+            .get_mut(&window)
+            .and_then(|window| window.workspaces.get_mut(&index))
+            .unwrap();
+        if workspace.floating_focus == Some(id) {
+            workspace.floating_focus = None;
+        }
+        let position = workspace
+            .floating
+            .iter()
+            .position(|float| float.pane.id == id)
+            .unwrap();
+        workspace.floating.remove(position)
+    }
+
+    /// Bounds of a floating terminal taking the given share of the window,
+    /// centered or dropped down from the top edge.
+    fn float_bounds(&self, width: f32, height: f32, drop_down: bool) -> Rectangle {
+        share(self.current().size, width, height, drop_down)
+    }
+
+    fn scratchpad_bounds(&self) -> Rectangle {
+        let scratchpad = self
+            .config
+            .as_ref()
+            .and_then(|config| config.scratchpad.as_ref());
+        self.float_bounds(
+            scratchpad
+                .and_then(|scratchpad| scratchpad.width)
+                .unwrap_or(0.8),
+            scratchpad
+                .and_then(|scratchpad| scratchpad.height)
+                .unwrap_or(0.5),
+            true,
+        )
+    }
+
+    /// Moves a floating terminal, keeping a bit of it inside the window.
+    fn set_float_bounds(&mut self, window: window::Id, id: usize, bounds: Rectangle) {
+        let Some(state) = self.windows.get_mut(&window) else {
+            return;
+        };
+        let bounds = keep_inside(bounds, state.size);
+        if let Some(float) = state
+            .active_mut()
+            .floating
+            .iter_mut()
+            .find(|float| float.pane.id == id)
+        {
+            float.bounds = bounds;
+        }
+    }
+
+    pub fn nudge_float(&mut self, id: usize, direction: pane_grid::Direction, kind: GrabKind) {
+        use pane_grid::Direction;
+
+        let size = self.current().size;
+        let step = Size::new(
+            size.width * self.resize_step,
+            size.height * self.resize_step,
+        );
+        let Some(bounds) = self
+            .active()
+            .floating
+            .iter()
+            .find(|float| float.pane.id == id)
+            .map(|float| float.bounds)
+        else {
+            return;
+        };
+        let (dx, dy) = match direction {
+            Direction::Left => (-step.width, 0.0),
+            Direction::Right => (step.width, 0.0),
+            Direction::Up => (0.0, -step.height),
+            Direction::Down => (0.0, step.height),
+        };
+        self.set_float_bounds(self.window, id, kind.apply(bounds, dx, dy));
+    }
+
+    pub fn view_float(
+        &self,
+        window: window::Id,
+        float: &Float,
+        focused: bool,
+    ) -> Element<'_, Message> {
+        const GRIP: f32 = 8.0;
+        let id = float.pane.id;
+        let content = column![
+            mouse_area(container(horizontal_space()).width(Fill).height(GRIP))
+                .on_press(Message::Grabbed(window, id, GrabKind::Move))
+                .interaction(mouse::Interaction::Grab),
+            view_terminal(&float.pane, &self.terminals),
+            row![
+                horizontal_space(),
+                mouse_area(container(horizontal_space()).width(GRIP).height(GRIP))
+                    .on_press(Message::Grabbed(window, id, GrabKind::Resize))
+                    .interaction(mouse::Interaction::ResizingDiagonallyDown),
+            ]
+            .height(GRIP),
+        ];
+
+        let intensity = self.bell_intensity(&float.pane, Instant::now());
+        let styles = self.styles;
+        let base: fn(&Styles, &Theme) -> container::Style = if focused {
+            Styles::float_focused
+        } else if float.pane.rang {
+            Styles::float_rang
+        } else {
+            Styles::float_unfocused
+        };
+        let frame = mouse_area(
+            container(content)
+                .width(float.bounds.width)
+                .height(float.bounds.height)
+                .style(move |theme| match intensity > 0.0 {
+                    true => styles.bell(theme, base(&styles, theme), intensity),
+                    false => base(&styles, theme),
+                }),
+        )
+        .on_press(Message::FocusFloat(window, id));
+
+        container(frame)
+            .padding(Padding {
+                top: float.bounds.y.max(0.0),
+                left: float.bounds.x.max(0.0),
+                ..Padding::ZERO
+            })
+            .width(Fill)
+            .height(Fill)
+            .into()
+    }
+}
+
+/// Bounds taking the given share of `window`, centered or dropped down from
+/// the top edge.
+fn share(window: Size, width: f32, height: f32, drop_down: bool) -> Rectangle {
+    let size = Size::new(window.width * width, window.height * height);
+    let y = if drop_down {
+        0.0
+    } else {
+        (window.height - size.height) / 2.0
+    };
+    Rectangle::new(Point::new((window.width - size.width) / 2.0, y), size)
+}
+
+/// `bounds` at a usable size, with a bit of it left inside `window`.
+fn keep_inside(mut bounds: Rectangle, window: Size) -> Rectangle {
+    bounds.width = bounds.width.max(120.0);
+    bounds.height = bounds.height.max(80.0);
+    bounds.x = bounds.x.clamp(40.0 - bounds.width, window.width - 40.0);
+    bounds.y = bounds.y.clamp(0.0, window.height - 40.0);
+    bounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Size = Size::new(1000.0, 800.0);
+
+    #[test]
+    fn floats_are_centered_and_the_scratchpad_drops_down() {
+        assert_eq!(
+            share(WINDOW, 0.6, 0.5, false),
+            Rectangle::new(Point::new(200.0, 200.0), Size::new(600.0, 400.0))
+        );
+        assert_eq!(
+            share(WINDOW, 0.8, 0.5, true),
+            Rectangle::new(Point::new(100.0, 0.0), Size::new(800.0, 400.0))
+        );
+    }
+
+    #[test]
+    fn grabs_move_or_resize() {
+        let bounds = Rectangle::new(Point::new(10.0, 20.0), Size::new(300.0, 200.0));
+        assert_eq!(
+            GrabKind::Move.apply(bounds, 5.0, -5.0),
+            Rectangle::new(Point::new(15.0, 15.0), Size::new(300.0, 200.0))
+        );
+        assert_eq!(
+            GrabKind::Resize.apply(bounds, 5.0, -5.0),
+            Rectangle::new(Point::new(10.0, 20.0), Size::new(305.0, 195.0))
+        );
+    }
+
+    #[test]
+    fn floats_keep_a_grip_inside_the_window() {
+        let tiny = Rectangle::new(Point::new(100.0, 100.0), Size::new(10.0, 10.0));
+        assert_eq!(keep_inside(tiny, WINDOW).size(), Size::new(120.0, 80.0));
+
+        let gone = Rectangle::new(Point::new(-5000.0, -50.0), Size::new(300.0, 200.0));
+        assert_eq!(
+            keep_inside(gone, WINDOW).position(),
+            Point::new(-260.0, 0.0)
+        );
+
+        let gone = Rectangle::new(Point::new(5000.0, 5000.0), Size::new(300.0, 200.0));
+        assert_eq!(
+            keep_inside(gone, WINDOW).position(),
+            Point::new(960.0, 760.0)
+        );
+    }
+}
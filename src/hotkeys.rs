@@ -0,0 +1,183 @@
+use alacritty_terminal::term::TermMode;
+use iced::keyboard::Modifiers;
+use iced::keyboard::key::{Key, Named};
+use iced::widget::pane_grid::Direction;
+
+use crate::Message;
+use crate::terminal::bindings::{Binding, BindingAction, InputKind};
+
+/// Named keys that `handle` may act on, checked when building the bindings.
+const NAMED_KEYS: [Named; 6] = [
+    Named::Enter,
+    Named::Space,
+    Named::ArrowUp,
+    Named::ArrowDown,
+    Named::ArrowLeft,
+    Named::ArrowRight,
+];
+
+/// The message for a key pressed with `modifiers`, if they hold
+/// `app_modifier` and the key is one of the hotkeys.
+pub fn handle(key: Key, modifiers: Modifiers, app_modifier: Modifiers) -> Option<Message> {
+    if modifiers.is_empty() || !modifiers.contains(app_modifier) {
+        return None;
+    }
+    let modifiers = modifiers.difference(app_modifier);
+    match key.as_ref() {
+        Key::Character(c) if modifiers.shift() => Some(Message::OpenLayout(c.to_lowercase())),
+        Key::Character("q") => Some(Message::CloseFocused),
+        Key::Character("n") => Some(Message::SplitFocused),
+        Key::Character("t") => Some(Message::TearOff),
+        Key::Character("z") => Some(Message::ToggleZoom),
+        Key::Character("p") => Some(Message::TogglePin),
+        Key::Character("c") => Some(Message::NewTab),
+        Key::Character("]") => Some(Message::CycleTab(1)),
+        Key::Character("[") => Some(Message::CycleTab(-1)),
+        Key::Character("b") => Some(Message::BreakTab),
+        Key::Character("s") => Some(Message::CycleBroadcast),
+        Key::Character("x") => Some(Message::ToggleMark),
+        Key::Character("a") => Some(Message::CycleMonitor),
+        Key::Character("f") => Some(Message::ToggleFloat),
+        Key::Character("`") => Some(Message::ToggleScratchpad),
+        Key::Character("o") => Some(Message::CloseOthers),
+        Key::Character("r") => Some(Message::RotateSplit),
+        Key::Character("e") => Some(Message::EqualizeSplits),
+        Key::Character("m") => Some(Message::MoveToNextWindow),
+        Key::Character("h") => Some(Message::ResizeMaster(-0.05)),
+        Key::Character("l") => Some(Message::ResizeMaster(0.05)),
+        Key::Character("i") => Some(Message::ChangeMasterCount(1)),
+        Key::Character("d") => Some(Message::ChangeMasterCount(-1)),
+        Key::Character(c) => c
+            .parse()
+            .ok()
+            .filter(|index| *index != 0)
+            .map(Message::FocusWorkspace),
+        Key::Named(Named::Enter) if modifiers.shift() => Some(Message::PromoteFocused),
+        Key::Named(Named::Enter) => Some(Message::NewWindow),
+        Key::Named(Named::Space) => Some(Message::CycleTiling),
+        Key::Named(key) => {
+            let direction = match key {
+                Named::ArrowUp => Some(Direction::Up),
+                Named::ArrowDown => Some(Direction::Down),
+                Named::ArrowLeft => Some(Direction::Left),
+                Named::ArrowRight => Some(Direction::Right),
+                _ => None,
+            };
+
+            if modifiers.shift() {
+                direction.map(Message::SwapFocused)
+            } else if modifiers.control() {
+                direction.map(Message::ResizeFocused)
+            } else {
+                direction.map(Message::FocusAdjacent)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Bindings for every key `handle` acts on, so terminals leave them alone
+/// and the rest of the keys held with the app modifier still reach the
+/// program. Layouts only take the keys in `layout_keys`.
+pub fn bindings(
+    app_modifier: Modifiers,
+    layout_keys: &[String],
+) -> Vec<(Binding<InputKind>, BindingAction)> {
+    let characters = ('!'..='~')
+        .filter(|c| !c.is_ascii_uppercase())
+        .map(|c| Key::Character(c.to_string().into()));
+    let named = NAMED_KEYS.into_iter().map(Key::Named);
+
+    let mut bindings = vec![];
+    for key in characters.chain(named) {
+        for bits in 0..16 {
+            let mut modifiers = Modifiers::empty();
+            modifiers.set(Modifiers::SHIFT, bits & 1 != 0);
+            modifiers.set(Modifiers::ALT, bits & 2 != 0);
+            modifiers.set(Modifiers::CTRL, bits & 4 != 0);
+            modifiers.set(Modifiers::LOGO, bits & 8 != 0);
+            if modifiers.intersects(app_modifier) {
+                continue;
+            }
+
+            match handle(key.clone(), modifiers | app_modifier, app_modifier) {
+                Some(Message::OpenLayout(c)) if !layout_keys.contains(&c) => continue,
+                Some(_) => {}
+                None => continue,
+            }
+
+            let target = match &key {
+                Key::Character(c) => InputKind::Char(c.to_string()),
+                Key::Named(named) => InputKind::KeyCode(*named),
+                Key::Unidentified => continue,
+            };
+            let binding = Binding {
+                target,
+                modifiers: modifiers | app_modifier,
+                terminal_mode_include: TermMode::empty(),
+                terminal_mode_exclude: TermMode::empty(),
+            };
+            bindings.push((binding, BindingAction::Hotkey));
+        }
+    }
+    bindings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::settings::KeyboardSettings;
+
+    fn char(c: &str) -> Key {
+        Key::Character(c.into())
+    }
+
+    #[test]
+    fn default_hotkeys_leave_readline_alt_keys_alone() {
+        let app_modifier = KeyboardSettings::default().app_modifier;
+        for c in ["b", "f", "d", "c", "t", "l", "h", "."] {
+            assert!(
+                handle(char(c), Modifiers::ALT, app_modifier).is_none(),
+                "ALT + {c}"
+            );
+        }
+        assert!(matches!(
+            handle(char("f"), Modifiers::LOGO, app_modifier),
+            Some(Message::ToggleFloat)
+        ));
+    }
+
+    #[test]
+    fn extra_modifiers_pick_the_variant() {
+        let app_modifier = Modifiers::ALT;
+        let up = Key::Named(Named::ArrowUp);
+        assert!(matches!(
+            handle(up.clone(), Modifiers::ALT, app_modifier),
+            Some(Message::FocusAdjacent(Direction::Up))
+        ));
+        assert!(matches!(
+            handle(up.clone(), Modifiers::ALT | Modifiers::SHIFT, app_modifier),
+            Some(Message::SwapFocused(Direction::Up))
+        ));
+        assert!(matches!(
+            handle(up, Modifiers::ALT | Modifiers::CTRL, app_modifier),
+            Some(Message::ResizeFocused(Direction::Up))
+        ));
+        assert!(handle(char("q"), Modifiers::CTRL, app_modifier).is_none());
+    }
+
+    #[test]
+    fn bindings_only_cover_keys_with_hotkeys() {
+        let bindings = bindings(Modifiers::LOGO, &["d".to_string()]);
+        let bound = |c: &str, modifiers: Modifiers| {
+            bindings.iter().any(|(binding, _)| {
+                binding.target == InputKind::Char(c.to_string()) && binding.modifiers == modifiers
+            })
+        };
+        assert!(bound("q", Modifiers::LOGO));
+        assert!(bound("d", Modifiers::LOGO | Modifiers::SHIFT));
+        assert!(!bound("g", Modifiers::LOGO));
+        assert!(!bound("e", Modifiers::LOGO | Modifiers::SHIFT));
+        assert!(!bound("b", Modifiers::ALT));
+    }
+}
@@ -0,0 +1,66 @@
+use iced::widget::pane_grid::{Axis, Configuration};
+use std::env;
+use std::path::PathBuf;
+
+use crate::config::{Command, LayoutAxis, LayoutNode, LayoutPane};
+use crate::terminal::settings::BackendSettings;
+
+/// Walks a declared layout tree and turns it into a pane grid configuration,
+/// calling `spawn` once for every leaf in left-to-right order.
+pub fn build<T>(node: &LayoutNode, spawn: &mut impl FnMut(&LayoutPane) -> T) -> Configuration<T> {
+    match node {
+        LayoutNode::Split(split) => {
+            let axis = match split.axis {
+                LayoutAxis::Horizontal => Axis::Horizontal,
+                LayoutAxis::Vertical => Axis::Vertical,
+            };
+            let a = build(&split.a, spawn);
+            let b = build(&split.b, spawn);
+
+            Configuration::Split {
+                axis,
+                ratio: split.ratio.unwrap_or(0.5).clamp(0.05, 0.95),
+                a: Box::new(a),
+                b: Box::new(b),
+            }
+        }
+        LayoutNode::Pane(pane) => Configuration::Pane(spawn(pane)),
+    }
+}
+
+/// Backend settings for a layout leaf. Anything the leaf leaves unset is taken
+/// from `base`.
+pub fn backend_settings(pane: &LayoutPane, base: &BackendSettings) -> BackendSettings {
+    let mut settings = base.clone();
+
+    match &pane.command {
+        Some(Command::Simple(program)) => {
+            settings.program = program.clone();
+            settings.args = vec![];
+        }
+        Some(Command::Complex { program, args }) => {
+            settings.program = program.clone();
+            settings.args = args.clone();
+        }
+        None => {}
+    }
+
+    if let Some(cwd) = &pane.cwd {
+        settings.working_directory = Some(expand_home(cwd));
+    }
+
+    if let Some(env) = &pane.env {
+        settings.env.extend(env.clone());
+    }
+
+    settings
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~"), env::var("HOME")) {
+        (Some(rest), Ok(home)) if rest.is_empty() || rest.starts_with('/') => {
+            PathBuf::from(format!("{home}{rest}"))
+        }
+        _ => PathBuf::from(path),
+    }
+}
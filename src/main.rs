@@ -1,94 +1,213 @@
+use broadcast::Broadcast;
 use config::Command;
+use confirm::view_confirm;
+use floating::{Grab, GrabKind};
 use iced::font::Family;
 use iced::theme::Palette;
 use iced::time::{self, Instant};
 use iced::widget::pane_grid::{self, PaneGrid};
-use iced::widget::{container, image, responsive, stack};
-use iced::window::settings::PlatformSpecific;
-use iced::window::{Level, icon};
+use iced::widget::{container, horizontal_space, image, responsive, stack, text};
 use iced::{Color, Task, theme};
-use iced::{Element, Fill, Font, Length, Size, Subscription, window};
-use iced::{Theme, keyboard};
-use std::collections::HashMap;
+use iced::{Element, Fill, Font, Length, Point, Size, Subscription, window};
+use iced::{Theme, event, keyboard, mouse};
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::process;
+use std::thread;
+use style::Styles;
 use terminal::TerminalView;
+use tiling::Tiling;
+use windows::Window;
+use workspace::Pane;
 
+mod broadcast;
 mod config;
+mod confirm;
+mod daemon;
+mod floating;
+mod hotkeys;
+mod layout;
 mod style;
+mod tabs;
 mod terminal;
+mod tiling;
+mod windows;
+mod workspace;
 
 pub fn main() -> iced::Result {
     unsafe {
         env::set_var("TERM", "frostty");
     }
-    let mut size = (790.0, 460.0);
-    let mut level = Level::Normal;
-    if let Some(win_cfg) = config::Config::new().and_then(|config| config.window) {
-        if let Some(level_string) = win_cfg.level {
-            if level_string == "AlwaysOnTop" {
-                level = Level::AlwaysOnTop;
-            }
-        }
 
-        if let Some(dim) = win_cfg.dimensions {
-            size = (dim.width, dim.height);
+    let mut layout = None;
+    let mut attach = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--daemon" => {
+                if let Err(err) = daemon::run() {
+                    eprintln!("frostty daemon: {}", err);
+                    process::exit(1);
+                }
+                return Ok(());
+            }
+            "--attach" => attach = true,
+            "--layout" => match args.next() {
+                Some(name) => layout = Some(name),
+                None => {
+                    eprintln!("--layout requires a layout name");
+                    process::exit(1);
+                }
+            },
+            _ => {
+                eprintln!("unknown argument: {}", arg);
+                process::exit(1);
+            }
         }
     }
 
-    iced::application("frostty", Frostty::update, Frostty::view)
+    iced::daemon(Frostty::title, Frostty::update, Frostty::view)
         .subscription(Frostty::subscription)
         .antialiasing(false)
         .theme(Frostty::theme)
-        .window(iced::window::Settings {
-            platform_specific: PlatformSpecific {
-                application_id: "frostty".to_string(),
-                override_redirect: false,
-            },
-            icon: Some(
-                iced::window::icon::from_file_data(
-                    include_bytes!("../extra/icons/hicolor/24x24/apps/frostty.png"),
-                    None,
-                )
-                .unwrap(),
-            ),
-            ..Default::default()
-        })
-        .transparent(true)
-        .window_size(size)
-        .level(level)
-        .run_with(Frostty::new)
+        .run_with(move || Frostty::new(layout, attach))
 }
 
 struct Frostty {
-    panes: pane_grid::State<Pane>,
+    windows: BTreeMap<window::Id, Window>,
+    window: window::Id,
     terminals: HashMap<u64, terminal::Terminal>,
     term_settings: terminal::settings::Settings,
     panes_created: usize,
-    bell: Option<pane_grid::Pane>,
+    /// How long the visual bell takes to fade, in milliseconds.
     bell_len: Option<u64>,
-    focus: Option<pane_grid::Pane>,
+    /// When the bell command last ran.
+    bell_command_at: Option<Instant>,
+    attach: bool,
+    tiling: Tiling,
+    master_ratio: f32,
+    master_count: usize,
+    resize_step: f32,
+    sticky_pins: bool,
+    /// Terminal id of the scratchpad while its shell is alive.
+    scratchpad: Option<usize>,
+    grab: Option<Grab>,
+    broadcast: Option<Broadcast>,
+    /// Process names that closing a terminal doesn't ask about, or `None`
+    /// when closing never asks.
+    confirm_close: Option<Vec<String>>,
+    window_title: String,
+    /// Shows a title bar on every pane when set.
+    pane_title: Option<String>,
     config: Option<config::Config>,
+    styles: Styles,
+}
+
+/// What makes a pane in the background ask for attention, like tmux's
+/// `monitor-activity` and `monitor-silence`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Monitor {
+    /// Any new output.
+    Activity,
+    /// No output for a while since the given time, when the monitor was set
+    /// or the pane last had focus.
+    Silence(Instant),
+}
+
+impl Monitor {
+    /// The monitor after `monitor` when cycling, ending with none. Silence
+    /// is counted from `now`.
+    fn next(monitor: Option<Self>, now: Instant) -> Option<Self> {
+        match monitor {
+            None => Some(Monitor::Activity),
+            Some(Monitor::Activity) => Some(Monitor::Silence(now)),
+            Some(Monitor::Silence(_)) => None,
+        }
+    }
+
+    /// Whether a silence monitor fires at `now`, given when the pane's
+    /// terminals last printed anything.
+    fn is_silent(
+        self,
+        outputs: impl IntoIterator<Item = Instant>,
+        now: Instant,
+        silence: std::time::Duration,
+    ) -> bool {
+        let Monitor::Silence(since) = self else {
+            return false;
+        };
+        let last_output = outputs.into_iter().fold(since, Instant::max);
+        now.duration_since(last_output) >= silence
+    }
 }
 
 #[derive(Debug, Clone)]
 enum Message {
+    KeyPressed(keyboard::Key, keyboard::Modifiers),
     SplitFocused,
     FocusAdjacent(pane_grid::Direction),
-    Clicked(pane_grid::Pane),
-    Dragged(pane_grid::DragEvent),
-    Resized(pane_grid::ResizeEvent),
-    Close(pane_grid::Pane),
+    FocusWorkspace(usize),
+    OpenLayout(String),
+    CycleTiling,
+    ToggleZoom,
+    ResizeFocused(pane_grid::Direction),
+    SwapFocused(pane_grid::Direction),
+    RotateSplit,
+    EqualizeSplits,
+    PromoteFocused,
+    ResizeMaster(f32),
+    ChangeMasterCount(i32),
+    NewWindow,
+    TearOff,
+    MoveToNextWindow,
+    WindowFocused(window::Id),
+    WindowUnfocused(window::Id),
+    WindowClosed(window::Id),
+    WindowResized(window::Id, Size),
+    Clicked(window::Id, pane_grid::Pane),
+    Dragged(window::Id, pane_grid::DragEvent),
+    Resized(window::Id, pane_grid::ResizeEvent),
     CloseFocused,
-    BellOn(pane_grid::Pane),
-    BellOff(Instant),
+    ConfirmClose(usize),
+    CancelClose(usize),
+    ConfirmPaste(usize),
+    CancelPaste(usize),
+    WindowCloseRequested(window::Id),
+    ConfirmCloseWindow(window::Id),
+    CancelCloseWindow(window::Id),
+    CloseOthers,
+    ConfirmCloseOthers(usize),
+    CancelCloseOthers(usize),
+    TogglePin,
+    NewTab,
+    CycleTab(i32),
+    SelectTab(window::Id, pane_grid::Pane, usize),
+    BreakTab,
+    CycleBroadcast,
+    CycleMonitor,
+    CheckSilence(Instant),
+    ToggleMark,
+    ToggleFloat,
+    ToggleScratchpad,
+    FocusFloat(window::Id, usize),
+    Grabbed(window::Id, usize, GrabKind),
+    GrabMoved(Point),
+    GrabReleased,
+    BellOn(u64),
+    BellFrame(Instant),
+    RefreshProcesses(Instant),
     Terminal(terminal::Event),
 }
 
 impl Frostty {
-    fn new() -> (Self, Task<Message>) {
+    fn new(layout: Option<String>, attach: bool) -> (Self, Task<Message>) {
         let config = config::Config::new();
-        let (panes, pane) = pane_grid::State::new(Pane::new(0));
+        let attach = attach
+            || config
+                .clone()
+                .and_then(|config| config.daemon)
+                .and_then(|daemon| daemon.attach)
+                .unwrap_or(false);
         let mut size = 14.0;
         let mut font_type = Font::MONOSPACE;
         if let Some(font) = config.clone().and_then(|config| config.font) {
@@ -114,198 +233,745 @@ impl Frostty {
                     .to_string(),
                 ..Default::default()
             },
+            keyboard: keyboard_settings(config.as_ref()),
         };
 
-        let term = terminal::Terminal::new(0, term_settings.clone());
-        let mut terminals = HashMap::new();
-        terminals.insert(0, term);
-
         let bell_len = config
             .clone()
             .and_then(|config| config.bell)
             .and_then(|bell| bell.duration);
 
-        let mut win_mode = window::Mode::Windowed;
-        let mut maximized = false;
-        if let Some(mode) = config
+        let tiling_cfg = config.clone().and_then(|config| config.tiling);
+        let tiling = tiling_cfg
+            .as_ref()
+            .and_then(|tiling| tiling.layout)
+            .unwrap_or(Tiling::Tile);
+        let master_ratio = tiling_cfg
+            .as_ref()
+            .and_then(|tiling| tiling.master_ratio)
+            .unwrap_or(0.55);
+        let master_count = tiling_cfg
+            .as_ref()
+            .and_then(|tiling| tiling.master_count)
+            .unwrap_or(1);
+        let resize_step = tiling_cfg
+            .as_ref()
+            .and_then(|tiling| tiling.resize_step)
+            .unwrap_or(0.05);
+        let sticky_pins = tiling_cfg
+            .as_ref()
+            .and_then(|tiling| tiling.sticky_pins)
+            .unwrap_or(false);
+        let confirm_close = config
             .clone()
-            .and_then(|config| config.window)
-            .and_then(|window| window.mode)
-        {
-            if mode == "Fullscreen" {
-                win_mode = window::Mode::Fullscreen;
-            }
-            if mode == "Maximized" {
-                maximized = true;
+            .and_then(|config| config.confirm_close)
+            .map_or(Some(vec![]), |confirm| {
+                confirm
+                    .enabled
+                    .unwrap_or(true)
+                    .then(|| confirm.ignore.unwrap_or_default())
+            });
+        let titles = config.clone().and_then(|config| config.titles);
+        let window_title = titles
+            .as_ref()
+            .and_then(|titles| titles.window.clone())
+            .unwrap_or("frostty".to_string());
+        let pane_title = titles.and_then(|titles| titles.pane);
+
+        let mut frostty = Frostty {
+            windows: BTreeMap::new(),
+            window: window::Id::unique(),
+            panes_created: 0,
+            bell_len,
+            bell_command_at: None,
+            attach,
+            tiling,
+            master_ratio,
+            master_count,
+            resize_step,
+            sticky_pins,
+            scratchpad: None,
+            grab: None,
+            broadcast: None,
+            confirm_close,
+            window_title,
+            pane_title,
+            terminals: HashMap::new(),
+            term_settings,
+            styles: Styles::new(config.as_ref()),
+            config,
+        };
+
+        // Reattach every session the daemon kept alive after its window closed.
+        let mut detached: BTreeMap<usize, Vec<u64>> = BTreeMap::new();
+        if attach {
+            match daemon::sessions() {
+                Ok(sessions) => {
+                    for session in sessions.iter().filter(|session| !session.attached) {
+                        detached
+                            .entry(session.workspace as usize)
+                            .or_default()
+                            .push(session.id);
+                    }
+                }
+                Err(err) => {
+                    eprintln!("failed to reach the frostty daemon: {}", err);
+                    frostty.attach = false;
+                }
             }
         }
 
-        (
-            Frostty {
-                panes,
-                panes_created: 1,
-                bell: None,
-                bell_len,
-                focus: Some(pane),
-                terminals,
-                term_settings,
-                config,
-            },
-            Task::batch([
-                iced::window::get_latest().and_then(move |id| window::change_mode(id, win_mode)),
-                iced::window::get_latest().and_then(move |id| window::maximize(id, maximized)),
-            ]),
-        )
+        let window = if detached.is_empty() {
+            let layout = layout.and_then(|name| {
+                let layout = frostty.layout(|layout| layout.name == name);
+                if layout.is_none() {
+                    eprintln!("no layout named {} found", name);
+                }
+                layout
+            });
+            let workspace = match layout {
+                Some(layout) => frostty.layout_workspace(&layout, 1),
+                None => frostty.shell_workspace(1),
+            };
+            Window::new(1, workspace)
+        } else {
+            let mut window = Window {
+                workspaces: BTreeMap::new(),
+                workspace: *detached.keys().next().unwrap(),
+                size: Size::ZERO,
+                focused: false,
+                confirm: None,
+            };
+            for (index, sessions) in detached {
+                let workspace = frostty.session_workspace(&sessions, index);
+                window.workspaces.insert(index, workspace);
+            }
+            window
+        };
+
+        let task = frostty.open_window(window);
+        (frostty, task)
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
+        let focused = self.focused_terminal();
+        let task = self.handle(message);
+        let now_focused = self.focused_terminal();
+        if now_focused != focused {
+            for (id, gained) in [(focused, false), (now_focused, true)] {
+                if let Some(terminal) = id.and_then(|id| self.terminals.get_mut(&(id as u64))) {
+                    terminal.report_focus(gained);
+                }
+            }
+        }
+        task
+    }
+
+    fn handle(&mut self, message: Message) -> Task<Message> {
         match message {
+            Message::KeyPressed(key, modifiers) => {
+                let app_modifier = self.term_settings.keyboard.app_modifier;
+                if let Some(message) = hotkeys::handle(key, modifiers, app_modifier) {
+                    return self.handle(message);
+                }
+            }
             Message::SplitFocused => {
-                if let Some(pane) = self.focus {
-                    let size = self
-                        .panes
-                        .layout()
-                        .pane_regions(0.0, Size::new(800.0, 600.0))
-                        .get(&pane)
-                        .unwrap()
-                        .clone();
-                    let axis = if size.width >= size.height {
-                        pane_grid::Axis::Vertical
-                    } else {
-                        pane_grid::Axis::Horizontal
+                let id = self
+                    .spawn_terminal(self.term_settings.backend.clone(), self.current().workspace);
+                match self.add_pane(Pane::new(id)) {
+                    Some(pane) => return self.focus_pane(pane),
+                    None => self.kill_terminal(id as u64),
+                }
+            }
+            Message::FocusAdjacent(direction) => {
+                let workspace = self.active();
+                if workspace.floating_focus.is_some() {
+                    // Any direction leaves the floating layer for the grid.
+                    if let Some(pane) = workspace.focus {
+                        return self.focus_pane(pane);
+                    }
+                }
+                if workspace.tiling == Tiling::Monocle {
+                    // Only one pane is visible, so every direction steps
+                    // through the pane order instead.
+                    let len = workspace.order.len();
+                    let step = match direction {
+                        pane_grid::Direction::Left | pane_grid::Direction::Up => len - 1,
+                        pane_grid::Direction::Right | pane_grid::Direction::Down => 1,
                     };
-                    let result = self.panes.split(axis, pane, Pane::new(self.panes_created));
+                    if let Some(position) = workspace
+                        .focused()
+                        .and_then(|id| workspace.order.iter().position(|other| *other == id))
+                        && let Some(pane) = workspace.find(workspace.order[(position + step) % len])
+                    {
+                        return self.focus_pane(pane);
+                    }
+                } else if let Some(pane) = workspace.focus
+                    && let Some(adjacent) = workspace.panes.adjacent(pane, direction)
+                {
+                    return self.focus_pane(adjacent);
+                }
+            }
+            Message::CycleTiling => {
+                let workspace = self.active_mut();
+                workspace.tiling = workspace.tiling.next();
+                workspace.panes.restore();
+                workspace.arrange();
+            }
+            Message::ToggleZoom => {
+                let workspace = self.active_mut();
+                if workspace.tiling != Tiling::Monocle {
+                    match (workspace.panes.maximized(), workspace.focus) {
+                        (Some(_), _) => workspace.panes.restore(),
+                        (None, Some(pane)) => workspace.panes.maximize(pane),
+                        (None, None) => {}
+                    }
+                }
+            }
+            Message::ResizeFocused(direction) => {
+                use pane_grid::Direction;
 
-                    let terminal = terminal::Terminal::new(
-                        self.panes_created as u64,
-                        self.term_settings.clone(),
-                    );
-                    let command = TerminalView::focus(terminal.widget_id());
-                    self.terminals.insert(self.panes_created as u64, terminal);
+                if let Some(id) = self.active().floating_focus {
+                    self.nudge_float(id, direction, GrabKind::Resize);
+                    return Task::none();
+                }
 
-                    if let Some((pane, _)) = result {
-                        self.focus = Some(pane);
+                let step = self.resize_step;
+                let workspace = self.active_mut();
+                let (axis, delta) = match direction {
+                    Direction::Left => (pane_grid::Axis::Vertical, -step),
+                    Direction::Right => (pane_grid::Axis::Vertical, step),
+                    Direction::Up => (pane_grid::Axis::Horizontal, -step),
+                    Direction::Down => (pane_grid::Axis::Horizontal, step),
+                };
+                // Moves the nearest border on that axis towards `direction`.
+                if let Some(pane) = workspace.focus
+                    && workspace.tiling != Tiling::Monocle
+                    && let Some((split, _, ratio)) =
+                        tiling::ancestors(workspace.panes.layout(), pane)
+                            .into_iter()
+                            .find(|(_, split_axis, _)| *split_axis == axis)
+                {
+                    workspace.tile_by_hand();
+                    workspace
+                        .panes
+                        .resize(split, (ratio + delta).clamp(0.05, 0.95));
+                }
+            }
+            Message::SwapFocused(direction) => {
+                if let Some(id) = self.active().floating_focus {
+                    self.nudge_float(id, direction, GrabKind::Move);
+                    return Task::none();
+                }
+                let workspace = self.active_mut();
+                if let Some(pane) = workspace.focus
+                    && let Some(adjacent) = workspace.panes.adjacent(pane, direction)
+                    && !workspace.is_pinned(pane)
+                    && !workspace.is_pinned(adjacent)
+                {
+                    let (a, b) = (
+                        workspace.panes.get(pane).unwrap().id,
+                        workspace.panes.get(adjacent).unwrap().id,
+                    );
+                    for id in workspace.order.iter_mut() {
+                        if *id == a {
+                            *id = b;
+                        } else if *id == b {
+                            *id = a;
+                        }
+                    }
+                    if workspace.tiling == Tiling::Manual {
+                        workspace.panes.swap(pane, adjacent);
+                    } else {
+                        workspace.arrange();
+                    }
+                    if let Some(pane) = self.active().find(a) {
+                        return self.focus_pane(pane);
                     }
-
-                    self.panes_created += 1;
-                    return command;
                 }
             }
-            Message::FocusAdjacent(direction) => {
-                if let Some(pane) = self.focus {
-                    if let Some(adjacent) = self.panes.adjacent(pane, direction) {
-                        let new_focused_pane = self.panes.get(adjacent).unwrap();
-                        let new_focued_terminal = self
-                            .terminals
-                            .get_mut(&(new_focused_pane.id as u64))
-                            .unwrap();
-                        self.focus = Some(adjacent);
-                        return TerminalView::focus(new_focued_terminal.widget_id());
+            Message::RotateSplit => {
+                let workspace = self.active_mut();
+                if let Some(pane) = workspace.focus
+                    && workspace.tile_by_hand()
+                {
+                    let configuration =
+                        tiling::rotate(workspace.panes.layout(), &workspace.panes, pane);
+                    workspace.rebuild(configuration);
+                }
+            }
+            Message::EqualizeSplits => {
+                let panes = &mut self.active_mut().panes;
+                let layout = panes.layout().clone();
+                tiling::equalize(&layout, &mut |split, ratio| panes.resize(split, ratio));
+            }
+            Message::PromoteFocused => {
+                let workspace = self.active_mut();
+                let mut unpinned = workspace.unpinned();
+                if let Some(id) = workspace.focused()
+                    && let Some(position) = unpinned.iter().position(|other| *other == id)
+                {
+                    // Like dwm's zoom, promoting the master swaps it with the
+                    // first pane of the stack.
+                    if position == 0 && unpinned.len() > 1 {
+                        unpinned.swap(0, 1);
+                    } else {
+                        let id = unpinned.remove(position);
+                        unpinned.insert(0, id);
+                    }
+                    workspace.place(unpinned);
+                    workspace.arrange();
+                }
+            }
+            Message::ResizeMaster(delta) => {
+                let workspace = self.active_mut();
+                workspace.master_ratio = (workspace.master_ratio + delta).clamp(0.05, 0.95);
+                workspace.arrange();
+            }
+            Message::ChangeMasterCount(delta) => {
+                let workspace = self.active_mut();
+                workspace.master_count =
+                    workspace.master_count.saturating_add_signed(delta as isize);
+                workspace.arrange();
+            }
+            Message::FocusWorkspace(index) => {
+                let previous = self.current().workspace;
+                if !self.current().workspaces.contains_key(&index) {
+                    let workspace = self.shell_workspace(index);
+                    self.current_mut().workspaces.insert(index, workspace);
+                }
+                self.current_mut().workspace = index;
+                if self.sticky_pins && previous != index && self.active().accepts_pane() {
+                    // Pinned panes follow the window to the workspace it shows.
+                    let pinned: Vec<usize> = self.current().workspaces[&previous]
+                        .panes
+                        .iter()
+                        .filter(|(_, pane)| pane.is_pinned)
+                        .map(|(_, pane)| pane.id)
+                        .collect();
+                    for id in pinned {
+                        if let Some(pane) = self.current().workspaces[&previous].find(id) {
+                            let (pane, _) = self.take_pane(self.window, previous, pane);
+                            if self.add_pane(pane.clone()).is_none() {
+                                self.kill_pane(pane);
+                            }
+                        }
                     }
                 }
+                return self.refocus();
             }
-            Message::Clicked(pane) => {
-                let new_focused_pane = self.panes.get(pane).unwrap();
-                let new_focued_terminal = self
-                    .terminals
-                    .get_mut(&(new_focused_pane.id as u64))
-                    .unwrap();
-                self.focus = Some(pane);
-                return TerminalView::focus(new_focued_terminal.widget_id());
+            Message::OpenLayout(key) => {
+                if let Some(layout) =
+                    self.layout(|layout| layout.keybind.as_deref() == Some(key.as_str()))
+                {
+                    let index = self.free_workspace();
+                    let workspace = self.layout_workspace(&layout, index);
+                    let window = self.current_mut();
+                    window.workspaces.insert(index, workspace);
+                    window.workspace = index;
+                    if let Some(pane) = self.active().focus {
+                        return self.focus_pane(pane);
+                    }
+                }
             }
-            Message::Resized(pane_grid::ResizeEvent { split, ratio }) => {
-                self.panes.resize(split, ratio);
+            Message::NewWindow => {
+                let workspace = self.shell_workspace(1);
+                return self.open_window(Window::new(1, workspace));
             }
-            Message::Dragged(pane_grid::DragEvent::Dropped { pane, target }) => {
-                self.panes.drop(pane, target);
+            Message::TearOff => return self.tear_off(),
+            Message::MoveToNextWindow => return self.move_to_next_window(),
+            Message::WindowFocused(id) => {
+                if let Some(window) = self.windows.get_mut(&id) {
+                    window.focused = true;
+                    self.window = id;
+                    return self.refocus();
+                }
             }
-            Message::Dragged(_) => {}
-            Message::Close(pane) => {
-                if let Some((cur, sibling)) = self.panes.close(pane) {
-                    self.terminals.remove(&(cur.id as u64));
-
-                    let new_focused_pane = self.panes.get(sibling).unwrap();
-                    let new_focued_terminal = self
-                        .terminals
-                        .get_mut(&(new_focused_pane.id as u64))
-                        .unwrap();
-                    self.focus = Some(sibling);
-                    return TerminalView::focus(new_focued_terminal.widget_id());
-                } else {
-                    return window::get_latest().and_then(window::close);
+            Message::WindowUnfocused(id) => {
+                if let Some(window) = self.windows.get_mut(&id) {
+                    window.focused = false;
                 }
             }
+            Message::WindowClosed(id) => return self.window_closed(id),
+            Message::WindowResized(id, size) => {
+                if let Some(window) = self.windows.get_mut(&id) {
+                    window.size = size;
+                }
+            }
+            Message::Clicked(window, pane) => {
+                self.window = window;
+                return self.focus_pane(pane);
+            }
+            Message::Resized(window, pane_grid::ResizeEvent { split, ratio }) => {
+                if let Some(window) = self.windows.get_mut(&window) {
+                    let workspace = window.active_mut();
+                    if workspace.tile_by_hand() {
+                        workspace.panes.resize(split, ratio);
+                    }
+                }
+            }
+            Message::Dragged(window, pane_grid::DragEvent::Dropped { pane, target }) => {
+                if let Some(window) = self.windows.get_mut(&window) {
+                    let workspace = window.active_mut();
+                    if workspace.is_pinned(pane) {
+                        // Pinned panes stay where they are.
+                    } else if workspace.tiling == Tiling::Manual {
+                        workspace.panes.drop(pane, target);
+                    } else if let pane_grid::Target::Pane(target, _) = target
+                        && !workspace.is_pinned(target)
+                        && let (Some(a), Some(b)) =
+                            (workspace.panes.get(pane), workspace.panes.get(target))
+                    {
+                        // Tiled panes trade places in the pane order instead.
+                        let (a, b) = (a.id, b.id);
+                        for id in workspace.order.iter_mut() {
+                            if *id == a {
+                                *id = b;
+                            } else if *id == b {
+                                *id = a;
+                            }
+                        }
+                        workspace.arrange();
+                    }
+                }
+            }
+            Message::Dragged(..) => {}
             Message::CloseFocused => {
-                if let Some(pane) = self.focus {
-                    if let Some(Pane { is_pinned, .. }) = self.panes.get(pane) {
-                        if !is_pinned {
-                            if let Some((cur, sibling)) = self.panes.close(pane) {
-                                self.terminals.remove(&(cur.id as u64));
-
-                                let new_focused_pane = self.panes.get(sibling).unwrap();
-                                let new_focued_terminal = self
-                                    .terminals
-                                    .get_mut(&(new_focused_pane.id as u64))
-                                    .unwrap();
-                                self.focus = Some(sibling);
-                                return TerminalView::focus(new_focued_terminal.widget_id());
-                            } else {
-                                return window::get_latest().and_then(window::close);
+                if let Some(id) = self.active().floating_focus {
+                    let running = self.running_command(id as u64);
+                    if let Some(pane) = self.pane_mut(id)
+                        && !pane.confirm_close(running)
+                    {
+                        return Task::none();
+                    }
+                    let float = self.remove_float(self.window, self.current().workspace, id);
+                    self.kill_terminal(float.pane.id as u64);
+                    return self.refocus();
+                }
+                if let Some(pane) = self.active().focus
+                    && let Some(state) = self.active().panes.get(pane)
+                    && !state.is_pinned
+                {
+                    let (id, tabs) = (state.id, state.tabs.len());
+                    let running = self.running_command(id as u64);
+                    if let Some(state) = self.pane_mut(id)
+                        && !state.confirm_close(running)
+                    {
+                        return Task::none();
+                    }
+                    if tabs > 1 {
+                        self.active_mut().remove_tab(pane, id);
+                        self.kill_terminal(id as u64);
+                        return self.focus_pane(pane);
+                    }
+                    return self.close_pane(self.window, self.current().workspace, pane);
+                }
+            }
+            Message::ConfirmClose(id) => return self.close_confirmed(id),
+            Message::CancelClose(id) => {
+                if let Some(pane) = self.pane_mut(id) {
+                    pane.confirm = None;
+                }
+            }
+            Message::ConfirmPaste(id) => {
+                if let Some(text) = self.pane_mut(id).and_then(|pane| pane.paste.take()) {
+                    let paste = terminal::Command::ProcessBackendCommand(
+                        terminal::BackendCommand::Paste(text),
+                    );
+                    for target in self.broadcast_targets(id as u64) {
+                        if let Some(terminal) = self.terminals.get_mut(&target) {
+                            terminal.update(paste.clone());
+                        }
+                    }
+                    if let Some(terminal) = self.terminals.get_mut(&(id as u64)) {
+                        terminal.update(paste);
+                    }
+                }
+                return self.refocus();
+            }
+            Message::CancelPaste(id) => {
+                if let Some(pane) = self.pane_mut(id) {
+                    pane.paste = None;
+                }
+                return self.refocus();
+            }
+            Message::WindowCloseRequested(id) => return self.request_window_close(id),
+            Message::ConfirmCloseWindow(id) => {
+                return window::close(id);
+            }
+            Message::CancelCloseWindow(id) => {
+                if let Some(window) = self.windows.get_mut(&id) {
+                    window.confirm = None;
+                }
+            }
+            Message::CloseOthers => {
+                let workspace = self.active();
+                let focused = workspace.focused();
+                let others: Vec<usize> = workspace
+                    .panes
+                    .iter()
+                    .filter(|(_, pane)| !pane.is_pinned && Some(pane.id) != focused)
+                    .map(|(_, pane)| pane.id)
+                    .collect();
+                let running = others
+                    .iter()
+                    .filter_map(|id| self.active().find(*id))
+                    .filter_map(|pane| self.active().panes.get(pane))
+                    .flat_map(|pane| pane.tabs.iter())
+                    .filter_map(|id| self.running_command(*id as u64))
+                    .collect();
+                if let Some(focused) = focused
+                    && let Some(pane) = self.pane_mut(focused)
+                    && !pane.confirm_close_others(running)
+                {
+                    return Task::none();
+                }
+                let index = self.current().workspace;
+                for id in others {
+                    if let Some(pane) = self.active().find(id) {
+                        let _ = self.close_pane(self.window, index, pane);
+                    }
+                }
+                if let Some(pane) = self.active().focus {
+                    return self.focus_pane(pane);
+                }
+            }
+            Message::ConfirmCloseOthers(id) => return self.close_others_confirmed(id),
+            Message::CancelCloseOthers(id) => {
+                if let Some(pane) = self.pane_mut(id) {
+                    pane.confirm_others = None;
+                }
+            }
+            Message::ToggleFloat => return self.toggle_float(),
+            Message::ToggleScratchpad => return self.toggle_scratchpad(),
+            Message::FocusFloat(window, id) => {
+                self.window = window;
+                return self.focus_float(id);
+            }
+            Message::Grabbed(window, id, kind) => return self.grab_float(window, id, kind),
+            Message::GrabMoved(position) => self.move_grab(position),
+            Message::GrabReleased => {
+                self.grab = None;
+            }
+            Message::TogglePin => {
+                if let Some(pane) = self.active().focus
+                    && let Some(state) = self.active_mut().panes.get_mut(pane)
+                {
+                    state.is_pinned = !state.is_pinned;
+                    // A pane held open by its pin closes once released.
+                    if !state.is_pinned && state.exited {
+                        return self.close_pane(self.window, self.current().workspace, pane);
+                    }
+                }
+            }
+            Message::NewTab => return self.new_tab(),
+            Message::CycleTab(step) => return self.cycle_tab(step),
+            Message::SelectTab(window, pane, tab) => return self.select_tab(window, pane, tab),
+            Message::BreakTab => return self.break_tab(),
+            Message::CycleMonitor => {
+                if let Some(pane) = self.active().focus
+                    && let Some(state) = self.active_mut().panes.get_mut(pane)
+                {
+                    state.monitor = Monitor::next(state.monitor, Instant::now());
+                    state.alert = false;
+                }
+            }
+            Message::CheckSilence(now) => {
+                let silence = std::time::Duration::from_secs(
+                    self.config
+                        .as_ref()
+                        .and_then(|config| config.monitor.as_ref())
+                        .and_then(|monitor| monitor.silence)
+                        .unwrap_or(30),
+                );
+                let mut silent = vec![];
+                for (window, state) in &self.windows {
+                    for (index, workspace) in &state.workspaces {
+                        for (pane, monitored) in workspace.panes.iter() {
+                            let outputs = monitored
+                                .tabs
+                                .iter()
+                                .filter_map(|tab| self.terminals.get(&(*tab as u64)))
+                                .map(|terminal| terminal.last_output);
+                            if monitored
+                                .monitor
+                                .is_some_and(|monitor| monitor.is_silent(outputs, now, silence))
+                            {
+                                silent.push((*window, *index, *pane));
                             }
                         }
                     }
                 }
+                for (window, index, pane) in silent {
+                    self.alert(window, index, pane);
+                }
+            }
+            Message::CycleBroadcast => {
+                self.broadcast = Broadcast::next(self.broadcast);
+            }
+            Message::ToggleMark => {
+                if let Some(pane) = self.active().focus
+                    && let Some(state) = self.active_mut().panes.get_mut(pane)
+                {
+                    state.marked = !state.marked;
+                }
             }
-            Message::BellOn(pane) => {
-                if let Some(duration) = self.bell_len {
-                    if duration != 0 {
-                        if let Some(command) = self
-                            .config
-                            .clone()
-                            .and_then(|config| config.bell)
-                            .and_then(|bell| bell.command)
+            Message::BellOn(id) => {
+                let now = Instant::now();
+                let bell = self.config.clone().and_then(|config| config.bell);
+                let interval = bell
+                    .as_ref()
+                    .and_then(|bell| bell.command_interval)
+                    .unwrap_or(1000);
+                if let Some(command) = bell.as_ref().and_then(|bell| bell.command.clone())
+                    && self.bell_command_at.is_none_or(|at| {
+                        now.duration_since(at) >= std::time::Duration::from_millis(interval)
+                    })
+                {
+                    self.bell_command_at = Some(now);
+                    spawn_command(command);
+                }
+
+                let window = self
+                    .locate(id)
+                    .map(|(window, _, _)| window)
+                    .or_else(|| self.locate_float(id as usize).map(|(window, _)| window));
+                let focused = self.is_focused_terminal(id as usize);
+                let flash = self.bell_len.is_some_and(|duration| duration != 0);
+                if let Some(pane) = self.pane_mut(id as usize) {
+                    if flash {
+                        pane.bell = Some(now);
+                    }
+                    pane.rang |= !focused;
+                }
+
+                if let Some(window) = window
+                    && self
+                        .windows
+                        .get(&window)
+                        .is_some_and(|state| !state.focused)
+                    && bell.and_then(|bell| bell.urgent).unwrap_or(true)
+                {
+                    return window::request_user_attention(
+                        window,
+                        Some(window::UserAttention::Informational),
+                    );
+                }
+            }
+            Message::BellFrame(now) => {
+                let duration = std::time::Duration::from_millis(self.bell_len.unwrap_or(0));
+                for window in self.windows.values_mut() {
+                    for workspace in window.workspaces.values_mut() {
+                        let floating = workspace.floating.iter_mut().map(|float| &mut float.pane);
+                        for pane in workspace
+                            .panes
+                            .iter_mut()
+                            .map(|(_, pane)| pane)
+                            .chain(floating)
                         {
-                            match command {
-                                Command::Simple(program) => {
-                                    process::Command::new(program)
-                                        .spawn()
-                                        .expect("failed to exec bell cmd");
-                                }
-                                Command::Complex { program, args } => {
-                                    process::Command::new(program)
-                                        .args(args)
-                                        .spawn()
-                                        .expect("failed to exec bell cmd");
-                                }
+                            if pane
+                                .bell
+                                .is_some_and(|rang| now.duration_since(rang) >= duration)
+                            {
+                                pane.bell = None;
                             }
                         }
-                        let bell_pane = self.panes.get_mut(pane).unwrap();
-                        if !bell_pane.bell {
-                            bell_pane.bell = true;
-                            self.bell = Some(pane);
-                        }
                     }
                 }
             }
-            Message::BellOff(_now) => {
-                if let Some(pane) = self.bell {
-                    let bell_pane = self.panes.get_mut(pane).unwrap();
-                    bell_pane.bell = false;
-                    self.bell = None;
+            Message::RefreshProcesses(_now) => {
+                for terminal in self.terminals.values_mut() {
+                    terminal.refresh_process();
                 }
             }
             Message::Terminal(terminal::Event::CommandReceived(id, cmd)) => {
+                if let terminal::Command::ProcessBackendCommand(terminal::BackendCommand::Paste(
+                    text,
+                )) = &cmd
+                    && self
+                        .config
+                        .as_ref()
+                        .and_then(|config| config.paste.as_ref())
+                        .and_then(|paste| paste.confirm)
+                        .unwrap_or(false)
+                    && self
+                        .terminals
+                        .get(&id)
+                        .is_some_and(|terminal| terminal.is_unsafe_paste(text))
+                    && let Some(pane) = self.pane_mut(id as usize)
+                {
+                    // Held back until confirmed over the terminal.
+                    pane.paste = Some(text.clone());
+                    return Task::none();
+                }
+                if let terminal::Command::ProcessBackendCommand(
+                    terminal::BackendCommand::Write(_) | terminal::BackendCommand::Paste(_),
+                ) = &cmd
+                {
+                    for target in self.broadcast_targets(id) {
+                        if let Some(terminal) = self.terminals.get_mut(&target) {
+                            terminal.update(cmd.clone());
+                        }
+                    }
+                }
+                let output = matches!(
+                    cmd,
+                    terminal::Command::ProcessBackendCommand(
+                        terminal::BackendCommand::ProcessAlacrittyEvent(
+                            terminal::AlacrittyEvent::Wakeup
+                        )
+                    )
+                );
                 if let Some(terminal) = self.terminals.get_mut(&id) {
-                    match terminal.update(cmd) {
+                    let action = terminal.update(cmd);
+                    if output
+                        && let Some((window, index, pane)) = self.locate(id)
+                        && self
+                            .pane(window, index, pane)
+                            .is_some_and(|pane| pane.monitor == Some(Monitor::Activity))
+                    {
+                        self.alert(window, index, pane);
+                    }
+                    match action {
                         terminal::actions::Action::Shutdown => {
-                            if let Some(cur_pane) = self.focus {
-                                return self.update(Message::Close(cur_pane));
+                            if let Some((window, index, pane)) = self.locate(id) {
+                                let workspace = self
+                                    .windows
+                                    .get_mut(&window)
+                                    .and_then(|window| window.workspaces.get_mut(&index))
+                                    .unwrap();
+                                if workspace.panes.get(pane).unwrap().tabs.len() > 1 {
+                                    // Only the tab goes while others remain.
+                                    workspace.remove_tab(pane, id as usize);
+                                    self.terminals.remove(&id);
+                                    if window == self.window {
+                                        return self.refocus();
+                                    }
+                                    return Task::none();
+                                }
+                                let state = workspace.panes.get_mut(pane).unwrap();
+                                // Pinned panes outlive their shell until unpinned.
+                                if state.is_pinned {
+                                    state.exited = true;
+                                } else {
+                                    return self.close_pane(window, index, pane);
+                                }
+                            } else {
+                                let id = id as usize;
+                                if let Some((window, index)) = self.locate_float(id) {
+                                    self.remove_float(window, index, id);
+                                }
+                                if self.scratchpad == Some(id) {
+                                    self.scratchpad = None;
+                                }
+                                self.terminals.remove(&(id as u64));
+                                if self.windows.contains_key(&self.window) {
+                                    return self.refocus();
+                                }
                             }
                         }
                         terminal::actions::Action::Bell => {
-                            if let Some(cur_pane) = self.focus {
-                                return self.update(Message::BellOn(cur_pane));
-                            }
+                            return self.update(Message::BellOn(id));
                         }
                         _ => (),
                     }
@@ -316,16 +982,180 @@ impl Frostty {
         Task::none()
     }
 
+    fn spawn_terminal(
+        &mut self,
+        mut settings: terminal::settings::BackendSettings,
+        workspace: usize,
+    ) -> usize {
+        if self.attach && settings.session.is_none() {
+            settings.session = Some(terminal::settings::Session::Create {
+                workspace: workspace as u32,
+            });
+        }
+        let id = self.panes_created;
+        let terminal = terminal::Terminal::new(
+            id as u64,
+            terminal::settings::Settings {
+                backend: settings,
+                ..self.term_settings.clone()
+            },
+        );
+        self.terminals.insert(id as u64, terminal);
+        self.panes_created += 1;
+        id
+    }
+
+    fn focus_pane(&mut self, pane: pane_grid::Pane) -> Task<Message> {
+        let workspace = self.active_mut();
+        workspace.focus = Some(pane);
+        workspace.floating_focus = None;
+        if let Some(state) = workspace.panes.get_mut(pane) {
+            state.alert = false;
+            state.rang = false;
+            // Silence counts again from when the pane was last looked at.
+            if let Some(Monitor::Silence(since)) = state.monitor.as_mut() {
+                *since = Instant::now();
+            }
+        }
+        if workspace.tiling == Tiling::Monocle {
+            workspace.panes.maximize(pane);
+        } else if workspace
+            .panes
+            .maximized()
+            .is_some_and(|zoomed| zoomed != pane)
+        {
+            // Moving focus away from a zoomed pane brings the others back.
+            workspace.panes.restore();
+        }
+        let id = workspace.panes.get(pane).unwrap().id as u64;
+        TerminalView::focus(self.terminals.get(&id).unwrap().widget_id())
+    }
+
+    /// Gives focus back to whatever had it last in the focused workspace.
+    fn refocus(&mut self) -> Task<Message> {
+        let workspace = self.active();
+        match (workspace.floating_focus, workspace.focus) {
+            (Some(id), _) => self.focus_float(id),
+            (None, Some(pane)) => self.focus_pane(pane),
+            (None, None) => Task::none(),
+        }
+    }
+
+    fn pane(&self, window: window::Id, index: usize, pane: pane_grid::Pane) -> Option<&Pane> {
+        self.windows
+            .get(&window)
+            .and_then(|window| window.workspaces.get(&index))
+            .and_then(|workspace| workspace.panes.get(pane))
+    }
+
+    /// Marks a pane whose monitor fired, unless it has focus, and runs the
+    /// configured command the first time.
+    fn alert(&mut self, window: window::Id, index: usize, pane: pane_grid::Pane) {
+        let focused = window == self.window
+            && self.windows.get(&window).is_some_and(|state| {
+                state.workspace == index
+                    && state.active().focus == Some(pane)
+                    && state.active().floating_focus.is_none()
+            });
+        if focused {
+            return;
+        }
+        let Some(state) = self
+            .windows
+            .get_mut(&window)
+            .and_then(|window| window.workspaces.get_mut(&index))
+            .and_then(|workspace| workspace.panes.get_mut(pane))
+        else {
+            return;
+        };
+        if state.alert {
+            return;
+        }
+        state.alert = true;
+        if let Some(command) = self
+            .config
+            .clone()
+            .and_then(|config| config.monitor)
+            .and_then(|monitor| monitor.command)
+        {
+            spawn_command(command);
+        }
+    }
+
+    /// The pane holding terminal `id`, tiled or floating.
+    fn pane_mut(&mut self, id: usize) -> Option<&mut Pane> {
+        self.windows
+            .values_mut()
+            .flat_map(|window| window.workspaces.values_mut())
+            .find_map(|workspace| {
+                let floating = workspace.floating.iter_mut().map(|float| &mut float.pane);
+                workspace
+                    .panes
+                    .iter_mut()
+                    .map(|(_, pane)| pane)
+                    .chain(floating)
+                    .find(|pane| pane.tabs.contains(&id))
+            })
+    }
+
+    /// The terminal being typed into, if a window has focus.
+    fn focused_terminal(&self) -> Option<usize> {
+        self.windows
+            .get(&self.window)
+            .filter(|window| window.focused)
+            .and_then(|window| {
+                let workspace = window.active();
+                workspace.floating_focus.or(workspace.focused())
+            })
+    }
+
+    /// Whether terminal `id` is the one being typed into.
+    fn is_focused_terminal(&self, id: usize) -> bool {
+        self.focused_terminal() == Some(id)
+    }
+
+    /// How strongly the visual bell of `pane` shows, fading from one to zero.
+    fn bell_intensity(&self, pane: &Pane, now: Instant) -> f32 {
+        let (Some(rang), Some(duration)) = (pane.bell, self.bell_len.filter(|len| *len != 0))
+        else {
+            return 0.0;
+        };
+        bell_fade(now.duration_since(rang), duration)
+    }
+
+    fn kill_terminal(&mut self, id: u64) {
+        if let Some(terminal) = self.terminals.remove(&id) {
+            terminal.kill();
+        }
+    }
+
     fn subscription(&self) -> Subscription<Message> {
         let mut subs = vec![];
-        let key_sub = keyboard::on_key_press(|key_code, modifiers| {
-            if modifiers.alt() {
-                return handle_hotkey(key_code);
-            }
-            None
-        });
+        let key_sub =
+            keyboard::on_key_press(|key, modifiers| Some(Message::KeyPressed(key, modifiers)));
 
         subs.push(key_sub);
+        subs.push(event::listen_with(|event, _status, id| match event {
+            iced::Event::Window(window::Event::Focused) => Some(Message::WindowFocused(id)),
+            iced::Event::Window(window::Event::Unfocused) => Some(Message::WindowUnfocused(id)),
+            iced::Event::Window(window::Event::Resized(size)) => {
+                Some(Message::WindowResized(id, size))
+            }
+            _ => None,
+        }));
+        subs.push(window::close_events().map(Message::WindowClosed));
+        subs.push(window::close_requests().map(Message::WindowCloseRequested));
+        if self.grab.is_some() {
+            subs.push(event::listen_with(|event, _status, _id| match event {
+                iced::Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                    Some(Message::GrabMoved(position))
+                }
+                iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    Some(Message::GrabReleased)
+                }
+                _ => None,
+            }));
+        }
 
         for id in self.terminals.keys() {
             let terminal = self.terminals.get(id).unwrap();
@@ -334,19 +1164,66 @@ impl Frostty {
                 Subscription::run_with_id(terminal.id, term_event_stream).map(Message::Terminal),
             );
         }
-        if let Some(duration) = self.bell_len {
-            if duration != 0 {
-                subs.push(
-                    time::every(std::time::Duration::from_millis(duration)).map(Message::BellOff),
-                );
-            }
+        // Fading bells redraw every frame until they are gone.
+        let ringing = self.windows.values().any(|window| {
+            window.workspaces.values().any(|workspace| {
+                workspace.panes.iter().any(|(_, pane)| pane.bell.is_some())
+                    || workspace
+                        .floating
+                        .iter()
+                        .any(|float| float.pane.bell.is_some())
+            })
+        });
+        if ringing {
+            subs.push(window::frames().map(Message::BellFrame));
+        }
+
+        let silence = self.windows.values().any(|window| {
+            window.workspaces.values().any(|workspace| {
+                workspace
+                    .panes
+                    .iter()
+                    .any(|(_, pane)| matches!(pane.monitor, Some(Monitor::Silence(_))))
+            })
+        });
+        if silence {
+            subs.push(time::every(std::time::Duration::from_secs(1)).map(Message::CheckSilence));
+        }
+        // Titles showing the foreground program follow it as it changes.
+        if self.pane_title.is_some() || self.window_title.contains('{') {
+            subs.push(
+                time::every(std::time::Duration::from_secs(1)).map(Message::RefreshProcesses),
+            );
         }
 
         Subscription::batch(subs)
     }
 
-    fn view(&self) -> Element<Message> {
-        let focus = self.focus;
+    fn title(&self, id: window::Id) -> String {
+        let Some(window) = self.windows.get(&id) else {
+            return self.window_title.clone();
+        };
+        let workspace = window.active();
+        match workspace
+            .floating_focus
+            .or(workspace.focused())
+            .and_then(|id| self.terminals.get(&(id as u64)))
+        {
+            Some(terminal) => format_title(&self.window_title, terminal),
+            None => self.window_title.clone(),
+        }
+    }
+
+    fn view(&self, id: window::Id) -> Element<'_, Message> {
+        let Some(window) = self.windows.get(&id) else {
+            return horizontal_space().into();
+        };
+        let focused_window = id == self.window;
+        let floating_focus = window.active().floating_focus.filter(|_| focused_window);
+        let focus = window
+            .active()
+            .focus
+            .filter(|_| focused_window && floating_focus.is_none());
         let gaps = self
             .config
             .clone()
@@ -354,49 +1231,134 @@ impl Frostty {
             .and_then(|general| general.gaps)
             .unwrap_or(0.0);
 
-        let pane_grid = PaneGrid::new(&self.panes, |id, pane, _is_maximized| {
-            let is_focused = focus == Some(id);
+        let workspace = window.active();
+        let zoomed = workspace.tiling != Tiling::Monocle && workspace.panes.maximized().is_some();
+        let now = Instant::now();
+        // Panes hidden behind a zoomed one ring through it instead.
+        let hidden_bell = match workspace.panes.maximized() {
+            Some(maximized) => workspace
+                .panes
+                .iter()
+                .filter(|(pane_id, _)| **pane_id != maximized)
+                .map(|(_, pane)| (self.bell_intensity(pane, now), pane.rang))
+                .fold((0.0, false), |(intensity, rang), (other, other_rang)| {
+                    (f32::max(intensity, other), rang || other_rang)
+                }),
+            None => (0.0, false),
+        };
 
-            pane_grid::Content::new(responsive(move |_size| {
-                view_content(pane.id as u64, pane.bell, &self.terminals)
-            }))
-            .style(if is_focused {
-                if pane.bell {
-                    style::pane_bell
+        let pane_grid = PaneGrid::new(&workspace.panes, |pane_id, pane, is_maximized| {
+            let is_focused = focus == Some(pane_id);
+            let (intensity, rang) = match is_maximized {
+                true => (
+                    f32::max(self.bell_intensity(pane, now), hidden_bell.0),
+                    pane.rang || hidden_bell.1,
+                ),
+                false => (self.bell_intensity(pane, now), pane.rang),
+            };
+            let broadcasting = match self.broadcast {
+                Some(Broadcast::Marked) => pane.marked,
+                Some(_) => true,
+                None => false,
+            };
+
+            let content = pane_grid::Content::new(responsive(move |_size| {
+                view_terminal(pane, &self.terminals)
+            }));
+            let content = if pane.tabs.len() > 1 || self.pane_title.is_some() {
+                content.title_bar(self.view_title_bar(id, pane_id, pane))
+            } else {
+                content
+            };
+            let base: fn(&Styles, &Theme) -> container::Style = if is_focused {
+                if zoomed {
+                    Styles::pane_zoomed
+                } else if broadcasting {
+                    Styles::pane_broadcast_focused
+                } else if pane.is_pinned {
+                    Styles::pane_pinned_focused
                 } else {
-                    style::pane_focused
+                    Styles::pane_focused
                 }
+            } else if rang {
+                Styles::pane_rang
+            } else if pane.alert {
+                Styles::pane_alert
+            } else if broadcasting {
+                Styles::pane_broadcast
+            } else if pane.is_pinned {
+                Styles::pane_pinned
             } else {
-                style::pane_unfocused
+                Styles::pane_unfocused
+            };
+            let styles = self.styles;
+            content.style(move |theme| match intensity > 0.0 {
+                true => styles.bell(theme, base(&styles, theme), intensity),
+                false => base(&styles, theme),
             })
         })
         .width(Fill)
         .height(Fill)
         .spacing(gaps)
-        .on_click(Message::Clicked)
-        .on_drag(Message::Dragged)
-        .on_resize(10, Message::Resized);
+        .on_click(move |pane| Message::Clicked(id, pane))
+        .on_drag(move |event| Message::Dragged(id, event))
+        .on_resize(10, move |event| Message::Resized(id, event));
 
+        let mut layers: Vec<Element<'_, Message>> = vec![];
         if let Some(wallpaper) = self
             .config
             .clone()
             .and_then(|config| config.general)
             .and_then(|general| general.wallpaper)
         {
-            stack![
+            layers.push(
                 image(format!("{}/.config/frostty/{}", env!("HOME"), wallpaper))
-                    .content_fit(iced::ContentFit::Cover),
-                container(pane_grid).padding(gaps)
-            ]
-            .width(Fill)
-            .height(Fill)
-            .into()
-        } else {
-            container(pane_grid).padding(gaps).into()
+                    .content_fit(iced::ContentFit::Cover)
+                    .into(),
+            );
+        }
+        layers.push(container(pane_grid).padding(gaps).into());
+        for float in &workspace.floating {
+            layers.push(self.view_float(id, float, floating_focus == Some(float.pane.id)));
         }
+        // Other workspaces with panes that rang are listed in the corner.
+        let rung: Vec<String> = window
+            .workspaces
+            .iter()
+            .filter(|(index, _)| **index != window.workspace)
+            .filter(|(_, workspace)| {
+                workspace.panes.iter().any(|(_, pane)| pane.rang)
+                    || workspace.floating.iter().any(|float| float.pane.rang)
+            })
+            .map(|(index, _)| index.to_string())
+            .collect();
+        if !rung.is_empty() {
+            layers.push(
+                container(
+                    container(text(format!("bell: {}", rung.join(" "))).size(12))
+                        .padding([2, 8])
+                        .style(move |theme| self.styles.bell_indicator(theme)),
+                )
+                .padding(gaps + 4.0)
+                .width(Fill)
+                .align_x(iced::alignment::Horizontal::Right)
+                .into(),
+            );
+        }
+        if let Some(running) = &window.confirm {
+            layers.push(view_confirm(
+                "Close the window? These are still running:",
+                running,
+                "Close",
+                Message::ConfirmCloseWindow(id),
+                Message::CancelCloseWindow(id),
+            ));
+        }
+
+        stack(layers).width(Fill).height(Fill).into()
     }
 
-    fn theme(&self) -> Theme {
+    fn theme(&self, _id: window::Id) -> Theme {
         if let Some(app) = self
             .config
             .clone()
@@ -436,63 +1398,250 @@ impl Frostty {
     }
 }
 
-fn handle_hotkey(key: keyboard::Key) -> Option<Message> {
-    use keyboard::key::{self, Key};
-    use pane_grid::Direction;
-
-    match key.as_ref() {
-        // TODO: config file for this
-        Key::Character("q") => Some(Message::CloseFocused),
-        Key::Character("n") => Some(Message::SplitFocused),
-        Key::Named(key) => {
-            let direction = match key {
-                key::Named::ArrowUp => Some(Direction::Up),
-                key::Named::ArrowDown => Some(Direction::Down),
-                key::Named::ArrowLeft => Some(Direction::Left),
-                key::Named::ArrowRight => Some(Direction::Right),
-                _ => None,
-            };
+/// The strength of a visual bell `elapsed` into a fade lasting `duration`
+/// milliseconds.
+fn bell_fade(elapsed: std::time::Duration, duration: u64) -> f32 {
+    let t = (elapsed.as_millis() as f32 / duration as f32).min(1.0);
+    // Smoothstep, so the fade starts and ends gently.
+    1.0 - t * t * (3.0 - 2.0 * t)
+}
 
-            direction.map(Message::FocusAdjacent)
-        }
-        _ => None,
-    }
+/// Fills the `{title}`, `{process}`, `{cwd}` and `{cwd_basename}` fields of a
+/// title format from `terminal`.
+fn format_title(format: &str, terminal: &terminal::Terminal) -> String {
+    let home = env::var("HOME").ok().filter(|home| !home.is_empty());
+    fill_title(
+        format,
+        terminal.title.as_deref(),
+        terminal.process.as_ref(),
+        home.as_deref(),
+    )
 }
 
-#[derive(Clone, Copy)]
-struct Pane {
-    id: usize,
-    pub is_pinned: bool,
-    pub bell: bool,
+/// [`format_title`] with the terminal's fields given, and the working
+/// directory shortened to start at `~` when it is under `home`.
+fn fill_title(
+    format: &str,
+    title: Option<&str>,
+    process: Option<&terminal::Process>,
+    home: Option<&str>,
+) -> String {
+    let cwd = process.and_then(|process| process.cwd.as_deref());
+    let basename = cwd
+        .map(|cwd| match cwd.file_name() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => cwd.display().to_string(),
+        })
+        .unwrap_or_default();
+    let cwd = cwd
+        .map(|cwd| match home.map(|home| cwd.strip_prefix(home)) {
+            Some(Ok(relative)) if relative.as_os_str().is_empty() => "~".to_string(),
+            Some(Ok(relative)) => format!("~/{}", relative.display()),
+            _ => cwd.display().to_string(),
+        })
+        .unwrap_or_default();
+
+    format
+        .replace("{title}", title.unwrap_or_default())
+        .replace("{process}", process.map_or("", |process| &process.name))
+        .replace("{cwd_basename}", &basename)
+        .replace("{cwd}", &cwd)
 }
 
-impl Pane {
-    fn new(id: usize) -> Self {
-        Self {
-            id,
-            is_pinned: false,
-            bell: false,
+/// The terminal shown in `pane`, below any prompt waiting on an answer.
+fn view_terminal<'a>(
+    pane: &Pane,
+    terminals: &'a HashMap<u64, terminal::Terminal>,
+) -> Element<'a, Message> {
+    const PREVIEW_LINES: usize = 5;
+    const PREVIEW_WIDTH: usize = 80;
+    let content = view_content(pane.id as u64, terminals);
+    if let Some(running) = &pane.confirm {
+        return stack![
+            content,
+            view_confirm(
+                "Close the terminal? This is still running:",
+                std::slice::from_ref(running),
+                "Close",
+                Message::ConfirmClose(pane.id),
+                Message::CancelClose(pane.id),
+            )
+        ]
+        .into();
+    }
+    if let Some(running) = &pane.confirm_others {
+        return stack![
+            content,
+            view_confirm(
+                "Close the other terminals? These are still running:",
+                running,
+                "Close",
+                Message::ConfirmCloseOthers(pane.id),
+                Message::CancelCloseOthers(pane.id),
+            )
+        ]
+        .into();
+    }
+    if let Some(paste) = &pane.paste {
+        // Control characters are spelled out so nothing hides in the preview.
+        let mut preview: Vec<String> = paste
+            .lines()
+            .take(PREVIEW_LINES)
+            .map(|line| {
+                line.chars()
+                    .flat_map(char::escape_debug)
+                    .take(PREVIEW_WIDTH)
+                    .collect()
+            })
+            .collect();
+        let more = paste.lines().count().saturating_sub(PREVIEW_LINES);
+        if more > 0 {
+            preview.push(format!("and {} more lines", more));
         }
+        return stack![
+            content,
+            view_confirm(
+                "Paste this? It may run commands as soon as it is pasted:",
+                &preview,
+                "Paste",
+                Message::ConfirmPaste(pane.id),
+                Message::CancelPaste(pane.id),
+            )
+        ]
+        .into();
     }
+    content
 }
 
 fn view_content(
     pane_id: u64,
-    bell: bool,
     terminals: &HashMap<u64, terminal::Terminal>,
 ) -> Element<'_, Message> {
     let terminal = terminals.get(&pane_id).expect("terminal with id not found");
-    if !bell {
-        container(TerminalView::show(terminal).map(Message::Terminal))
-            .padding(5)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .into()
-    } else {
-        container(TerminalView::show(terminal).map(Message::Terminal))
-            .padding(5)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .into()
+    container(TerminalView::show(terminal).map(Message::Terminal))
+        .padding(5)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+/// Runs a configured command in the background, reaping it once it exits.
+/// A command that can't be started is reported and otherwise ignored.
+fn spawn_command(command: Command) {
+    let (program, args) = match command {
+        Command::Simple(program) => (program, vec![]),
+        Command::Complex { program, args } => (program, args),
+    };
+    match process::Command::new(&program).args(args).spawn() {
+        Ok(mut child) => {
+            thread::spawn(move || child.wait());
+        }
+        Err(err) => eprintln!("failed to run {}: {}", program, err),
+    }
+}
+
+/// Reads the keyboard settings once, with bindings for the hotkeys and the
+/// layouts' keybinds under the app modifier.
+fn keyboard_settings(config: Option<&config::Config>) -> terminal::settings::KeyboardSettings {
+    let mut keyboard = terminal::settings::KeyboardSettings::new(
+        config.and_then(|config| config.keyboard.clone()),
+    );
+    let layout_keys: Vec<String> = config
+        .and_then(|config| config.layouts.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|layout| layout.keybind.clone())
+        .collect();
+    keyboard.hotkeys = hotkeys::bindings(keyboard.app_modifier, &layout_keys);
+    keyboard
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn process(cwd: &str) -> terminal::Process {
+        terminal::Process {
+            pid: 1,
+            name: "vim".to_string(),
+            command: "vim notes.md".to_string(),
+            cwd: Some(PathBuf::from(cwd)),
+        }
+    }
+
+    fn fill(format: &str, cwd: &str) -> String {
+        fill_title(format, Some("shell"), Some(&process(cwd)), Some("/home/me"))
+    }
+
+    #[test]
+    fn titles_fill_every_field() {
+        assert_eq!(
+            fill("{title} | {process} in {cwd_basename}", "/srv/app"),
+            "shell | vim in app"
+        );
+        assert_eq!(fill("{cwd}", "/srv/app"), "/srv/app");
+    }
+
+    #[test]
+    fn working_directories_under_home_start_at_tilde() {
+        assert_eq!(fill("{cwd}", "/home/me"), "~");
+        assert_eq!(fill("{cwd}", "/home/me/src/frostty"), "~/src/frostty");
+        // Only whole path components count as being under home.
+        assert_eq!(fill("{cwd}", "/home/meg"), "/home/meg");
+    }
+
+    #[test]
+    fn the_root_directory_is_its_own_basename() {
+        assert_eq!(fill("{cwd_basename}", "/"), "/");
+    }
+
+    #[test]
+    fn monitors_cycle_through_activity_and_silence() {
+        let now = Instant::now();
+        let activity = Monitor::next(None, now);
+        assert_eq!(activity, Some(Monitor::Activity));
+        let silence = Monitor::next(activity, now);
+        assert_eq!(silence, Some(Monitor::Silence(now)));
+        assert_eq!(Monitor::next(silence, now), None);
+    }
+
+    #[test]
+    fn silence_counts_from_the_last_output() {
+        const SILENCE: Duration = Duration::from_secs(30);
+        let since = Instant::now();
+        let monitor = Monitor::Silence(since);
+        assert!(!monitor.is_silent([], since + SILENCE / 2, SILENCE));
+        assert!(monitor.is_silent([], since + SILENCE, SILENCE));
+        // Output in any tab restarts the count.
+        let outputs = [since, since + SILENCE / 2];
+        assert!(!monitor.is_silent(outputs, since + SILENCE, SILENCE));
+        assert!(!Monitor::Activity.is_silent([], since + SILENCE, SILENCE));
+    }
+
+    #[test]
+    fn the_bell_fades_out_gently() {
+        let fade = |millis| bell_fade(Duration::from_millis(millis), 200);
+        assert_eq!(fade(0), 1.0);
+        assert_eq!(fade(100), 0.5);
+        assert_eq!(fade(200), 0.0);
+        assert_eq!(fade(1000), 0.0);
+        // Slow at both ends, fastest in the middle.
+        assert!(1.0 - fade(20) < fade(90) - fade(110));
+        assert!(fade(180) < fade(90) - fade(110));
+    }
+
+    #[test]
+    fn commands_that_fail_to_start_are_only_reported() {
+        spawn_command(Command::Simple("/nonexistent/frostty-monitor".to_string()));
+    }
+
+    #[test]
+    fn missing_fields_are_left_empty() {
+        assert_eq!(
+            fill_title("[{title}|{process}|{cwd}]", None, None, None),
+            "[||]"
+        );
     }
 }
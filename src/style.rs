@@ -1,84 +1,246 @@
+use iced::border::Radius;
 use iced::widget::container;
 use iced::{Background, Border, Color, Theme};
 
 use crate::config;
 
-pub fn pane_unfocused(theme: &Theme) -> container::Style {
-    let palette = theme.palette();
-    let config = config::Config::new();
-    let radius = match config
-        .clone()
-        .and_then(|config| config.border)
-        .and_then(|border| border.radius)
-    {
-        Some(rad) => iced::border::Radius::new(rad),
-        None => iced::border::Radius::default(),
-    };
-    let width = match config
-        .clone()
-        .and_then(|config| config.border)
-        .and_then(|border| border.thickness)
-    {
-        Some(width) => width,
-        None => 2.0,
-    };
+/// The pane colors and border from the config, resolved once since styles
+/// are asked for on every frame.
+#[derive(Debug, Clone, Copy)]
+pub struct Styles {
+    width: f32,
+    radius: Radius,
+    pinned: Color,
+    broadcast: Color,
+    alert: Color,
+    bell: Option<Color>,
+}
 
-    container::Style {
-        background: None,
-        border: Border {
-            width,
-            color: palette.text,
-            radius,
-        },
-        ..Default::default()
+impl Styles {
+    pub fn new(config: Option<&config::Config>) -> Self {
+        let border = config.and_then(|config| config.border.as_ref());
+        let app = config
+            .and_then(|config| config.colors.as_ref())
+            .and_then(|colors| colors.app.as_ref());
+        let color = |color: Option<&String>, default: &str, name: &str| {
+            let color = color.map_or(default, String::as_str);
+            Color::parse(color).unwrap_or_else(|| panic!("improperly formatted {name} color"))
+        };
+
+        Self {
+            width: border.and_then(|border| border.thickness).unwrap_or(2.0),
+            radius: border
+                .and_then(|border| border.radius)
+                .map_or_else(Radius::default, Radius::new),
+            pinned: color(app.and_then(|app| app.pinned.as_ref()), "#d28445", "pinned"),
+            broadcast: color(
+                app.and_then(|app| app.broadcast.as_ref()),
+                "#6a9fb5",
+                "broadcast",
+            ),
+            alert: color(app.and_then(|app| app.alert.as_ref()), "#f4bf75", "alert"),
+            bell: config
+                .and_then(|config| config.bell.as_ref())
+                .and_then(|bell| bell.color.as_ref())
+                .map(|bell| Color::parse(bell).expect("improperly formatted bell color")),
+        }
+    }
+
+    /// A transparent pane framed by the configured border in `color`.
+    fn pane(&self, color: Color) -> container::Style {
+        container::Style {
+            background: None,
+            border: Border {
+                width: self.width,
+                color,
+                radius: self.radius,
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn pane_unfocused(&self, theme: &Theme) -> container::Style {
+        self.pane(theme.palette().text)
+    }
+
+    pub fn pane_focused(&self, theme: &Theme) -> container::Style {
+        self.pane(theme.palette().primary)
+    }
+
+    pub fn pane_zoomed(&self, theme: &Theme) -> container::Style {
+        self.pane(theme.palette().success)
+    }
+
+    pub fn pane_pinned(&self, _theme: &Theme) -> container::Style {
+        self.pane(self.pinned)
+    }
+
+    /// A pinned pane that has focus keeps its pinned color with a doubled border.
+    pub fn pane_pinned_focused(&self, theme: &Theme) -> container::Style {
+        let mut style = self.pane_pinned(theme);
+        style.border.width *= 2.0;
+        style
+    }
+
+    /// A pane that repeats typed input to the others in its broadcast set.
+    pub fn pane_broadcast(&self, _theme: &Theme) -> container::Style {
+        self.pane(self.broadcast)
+    }
+
+    /// A background pane whose monitor saw activity or silence.
+    pub fn pane_alert(&self, _theme: &Theme) -> container::Style {
+        self.pane(self.alert)
+    }
+
+    pub fn pane_broadcast_focused(&self, theme: &Theme) -> container::Style {
+        let mut style = self.pane_broadcast(theme);
+        style.border.width *= 2.0;
+        style
+    }
+
+    /// Floating terminals are opaque so the panes below don't show through.
+    pub fn float_focused(&self, theme: &Theme) -> container::Style {
+        container::Style {
+            background: Some(Background::from(theme.palette().background)),
+            ..self.pane_focused(theme)
+        }
+    }
+
+    pub fn float_unfocused(&self, theme: &Theme) -> container::Style {
+        container::Style {
+            background: Some(Background::from(theme.palette().background)),
+            ..self.pane_unfocused(theme)
+        }
+    }
+
+    fn bell_color(&self, theme: &Theme) -> Color {
+        self.bell.unwrap_or(Color {
+            a: 0.3,
+            ..theme.palette().text
+        })
+    }
+
+    /// Lays the bell color over `style` at the given strength, for fading the
+    /// visual bell out.
+    pub fn bell(&self, theme: &Theme, style: container::Style, intensity: f32) -> container::Style {
+        let bell = self.bell_color(theme);
+        let alpha = bell.a * intensity;
+        let background = match style.background {
+            Some(Background::Color(base)) => Color {
+                r: base.r + (bell.r - base.r) * alpha,
+                g: base.g + (bell.g - base.g) * alpha,
+                b: base.b + (bell.b - base.b) * alpha,
+                a: base.a.max(alpha),
+            },
+            _ => Color { a: alpha, ..bell },
+        };
+
+        container::Style {
+            background: Some(Background::from(background)),
+            ..style
+        }
+    }
+
+    /// A pane in the background that rang its bell since it last had focus.
+    pub fn pane_rang(&self, theme: &Theme) -> container::Style {
+        self.pane(Color {
+            a: 1.0,
+            ..self.bell_color(theme)
+        })
+    }
+
+    pub fn float_rang(&self, theme: &Theme) -> container::Style {
+        container::Style {
+            background: Some(Background::from(theme.palette().background)),
+            ..self.pane_rang(theme)
+        }
+    }
+
+    /// The list of other workspaces holding panes that rang.
+    pub fn bell_indicator(&self, theme: &Theme) -> container::Style {
+        container::Style {
+            background: Some(Background::from(theme.palette().background)),
+            ..self.pane_rang(theme)
+        }
     }
 }
 
-pub fn pane_focused(theme: &Theme) -> container::Style {
+/// The prompt asking whether to close terminals with programs running.
+pub fn confirm(theme: &Theme) -> container::Style {
     let palette = theme.palette();
-    let config = config::Config::new();
-    let radius = match config
-        .clone()
-        .and_then(|config| config.border)
-        .and_then(|border| border.radius)
-    {
-        Some(rad) => iced::border::Radius::new(rad),
-        None => iced::border::Radius::default(),
-    };
-    let width = match config
-        .clone()
-        .and_then(|config| config.border)
-        .and_then(|border| border.thickness)
-    {
-        Some(width) => width,
-        None => 2.0,
-    };
-
     container::Style {
-        background: None,
+        background: Some(Background::from(palette.background)),
         border: Border {
-            width,
-            color: palette.primary,
-            radius,
+            width: 2.0,
+            color: palette.danger,
+            radius: iced::border::Radius::new(4.0),
         },
         ..Default::default()
     }
 }
 
-pub fn pane_bell(_theme: &Theme) -> container::Style {
-    let background = match config::Config::new()
-        .and_then(|config| config.bell)
-        .and_then(|bell| bell.color)
-    {
-        Some(color) => {
-            let bg = Color::parse(&color).expect("improperly formatted bell color");
-            Some(Background::from(bg))
-        }
-        None => None,
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    container::Style {
-        background,
-        ..Default::default()
+    const RED: Color = Color::from_rgb(1.0, 0.0, 0.0);
+
+    fn styles(config: &str) -> Styles {
+        Styles::new(Some(&toml::from_str(config).unwrap()))
+    }
+
+    #[test]
+    fn the_config_sets_the_border_and_colors() {
+        let styles =
+            styles("[border]\nradius = 6.0\nthickness = 3.0\n[colors.app]\npinned = \"#ff0000\"");
+        let pinned = styles.pane_pinned(&Theme::Dark).border;
+        assert_eq!((pinned.width, pinned.color), (3.0, RED));
+        assert_eq!(pinned.radius, Radius::new(6.0));
+        assert_eq!(styles.pane_pinned_focused(&Theme::Dark).border.width, 6.0);
+    }
+
+    #[test]
+    fn defaults_apply_without_a_config() {
+        let styles = Styles::new(None);
+        let border = styles.pane_focused(&Theme::Dark).border;
+        assert_eq!(border.width, 2.0);
+        assert_eq!(border.color, Theme::Dark.palette().primary);
+        assert_eq!(
+            styles.pane_broadcast(&Theme::Dark).border.color,
+            Color::parse("#6a9fb5").unwrap()
+        );
+    }
+
+    #[test]
+    fn the_bell_blends_into_the_background() {
+        let styles = styles("[bell]\ncolor = \"#ff0000\"");
+        let float = styles.float_unfocused(&Theme::Dark);
+        let background = Theme::Dark.palette().background;
+
+        let faded = styles.bell(&Theme::Dark, float, 0.0);
+        assert_eq!(faded.background, Some(Background::from(background)));
+        let rung = styles.bell(&Theme::Dark, float, 1.0);
+        assert_eq!(rung.background, Some(Background::from(RED)));
+
+        // Transparent panes are tinted with the bell color itself.
+        let pane = styles.pane_unfocused(&Theme::Dark);
+        let half = styles.bell(&Theme::Dark, pane, 0.5);
+        assert_eq!(
+            half.background,
+            Some(Background::from(Color { a: 0.5, ..RED }))
+        );
+    }
+
+    #[test]
+    fn panes_that_rang_are_framed_in_the_opaque_bell_color() {
+        let styles = Styles::new(None);
+        let color = styles.pane_rang(&Theme::Dark).border.color;
+        assert_eq!(
+            color,
+            Color {
+                a: 1.0,
+                ..Theme::Dark.palette().text
+            }
+        );
     }
 }
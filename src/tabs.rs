@@ -0,0 +1,225 @@
+use iced::widget::{button, pane_grid, row, text};
+use iced::{Task, window};
+
+use crate::workspace::{Pane, Workspace};
+use crate::{Frostty, Message, format_title};
+
+impl Workspace {
+    /// Shows the tab `tab` of `pane` in place of the current one.
+    fn show_tab(&mut self, pane: pane_grid::Pane, tab: usize) {
+        if let Some(state) = self.panes.get_mut(pane)
+            && state.tabs.contains(&tab)
+        {
+            let shown = std::mem::replace(&mut state.id, tab);
+            state.confirm = None;
+            for id in self.order.iter_mut().filter(|id| **id == shown) {
+                *id = tab;
+            }
+        }
+    }
+
+    /// Drops the tab `tab` from `pane`, showing the next one if it was shown.
+    /// The last tab of a pane is left for closing the pane itself.
+    pub fn remove_tab(&mut self, pane: pane_grid::Pane, tab: usize) {
+        let Some(state) = self.panes.get_mut(pane) else {
+            return;
+        };
+        let Some(position) = state.tabs.iter().position(|id| *id == tab) else {
+            return;
+        };
+        if state.tabs.len() == 1 {
+            return;
+        }
+        state.tabs.remove(position);
+        if state.id == tab {
+            let next = state.tabs[position.min(state.tabs.len() - 1)];
+            state.id = next;
+            for id in self.order.iter_mut().filter(|id| **id == tab) {
+                *id = next;
+            }
+        }
+    }
+}
+
+impl Frostty {
+    /// Opens a shell in a new tab next to the shown one in the focused pane.
+    pub fn new_tab(&mut self) -> Task<Message> {
+        if let Some(pane) = self.active().focus
+            && self.active().floating_focus.is_none()
+        {
+            let id =
+                self.spawn_terminal(self.term_settings.backend.clone(), self.current().workspace);
+            let workspace = self.active_mut();
+            if let Some(state) = workspace.panes.get_mut(pane) {
+                let position = state
+                    .tabs
+                    .iter()
+                    .position(|tab| *tab == state.id)
+                    .map_or(state.tabs.len(), |position| position + 1);
+                state.tabs.insert(position, id);
+            }
+            workspace.show_tab(pane, id);
+            return self.focus_pane(pane);
+        }
+        Task::none()
+    }
+
+    /// Shows the tab `step` places from the shown one, wrapping around.
+    pub fn cycle_tab(&mut self, step: i32) -> Task<Message> {
+        if let Some(pane) = self.active().focus
+            && self.active().floating_focus.is_none()
+            && let Some(state) = self.active().panes.get(pane)
+            && state.tabs.len() > 1
+        {
+            let position = state.tabs.iter().position(|tab| *tab == state.id);
+            let position = position.unwrap_or(0) as i32 + step;
+            let tab = state.tabs[position.rem_euclid(state.tabs.len() as i32) as usize];
+            self.active_mut().show_tab(pane, tab);
+            return self.focus_pane(pane);
+        }
+        Task::none()
+    }
+
+    /// Shows the tab clicked in a pane's title bar.
+    pub fn select_tab(
+        &mut self,
+        window: window::Id,
+        pane: pane_grid::Pane,
+        tab: usize,
+    ) -> Task<Message> {
+        if let Some(state) = self.windows.get_mut(&window)
+            && state.active().panes.get(pane).is_some()
+        {
+            state.active_mut().show_tab(pane, tab);
+            self.window = window;
+            return self.focus_pane(pane);
+        }
+        Task::none()
+    }
+
+    /// Moves the shown tab of the focused pane into a pane of its own.
+    pub fn break_tab(&mut self) -> Task<Message> {
+        if let Some(pane) = self.active().focus
+            && self.active().floating_focus.is_none()
+            && let Some(state) = self.active().panes.get(pane)
+            && state.tabs.len() > 1
+        {
+            let id = state.id;
+            self.active_mut().remove_tab(pane, id);
+            match self.add_pane(Pane::new(id)) {
+                Some(pane) => return self.focus_pane(pane),
+                None => {
+                    // Without room for a split the tab goes back on the stack.
+                    let workspace = self.active_mut();
+                    if let Some(state) = workspace.panes.get_mut(pane) {
+                        state.tabs.push(id);
+                    }
+                    workspace.show_tab(pane, id);
+                }
+            }
+        }
+        Task::none()
+    }
+
+    /// The title of a pane, or a strip of its tabs when it holds several
+    /// terminals.
+    pub fn view_title_bar(
+        &self,
+        window: window::Id,
+        pane_id: pane_grid::Pane,
+        pane: &Pane,
+    ) -> pane_grid::TitleBar<'_, Message> {
+        const MAX_LABEL: usize = 32;
+        let label = |index: usize, tab: usize| -> String {
+            let terminal = self.terminals.get(&(tab as u64));
+            let label = match (&self.pane_title, terminal) {
+                (Some(format), Some(terminal)) => Some(format_title(format, terminal)),
+                (_, terminal) => terminal.and_then(|terminal| terminal.title.clone()),
+            };
+            label.filter(|label| !label.is_empty()).map_or_else(
+                || (index + 1).to_string(),
+                |label| label.chars().take(MAX_LABEL).collect(),
+            )
+        };
+        if pane.tabs.len() == 1 {
+            return pane_grid::TitleBar::new(text(label(0, pane.id)).size(12)).padding([2, 8]);
+        }
+
+        let tabs = pane.tabs.iter().enumerate().map(|(index, tab)| {
+            button(text(label(index, *tab)).size(12))
+                .padding([2, 8])
+                .style(if *tab == pane.id {
+                    button::primary
+                } else {
+                    button::secondary
+                })
+                .on_press(Message::SelectTab(window, pane_id, *tab))
+                .into()
+        });
+
+        pane_grid::TitleBar::new(row(tabs).spacing(2)).padding(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tiling::Tiling;
+
+    /// A workspace beside terminal `9`, with a pane holding tabs `0` to `2`
+    /// showing `0`.
+    fn stacked() -> (Workspace, pane_grid::Pane) {
+        let mut workspace = Workspace::tiled(Tiling::Tile, &[0, 9]);
+        let pane = workspace.find(0).unwrap();
+        workspace.panes.get_mut(pane).unwrap().tabs = vec![0, 1, 2];
+        (workspace, pane)
+    }
+
+    #[test]
+    fn showing_a_tab_puts_it_in_the_pane_order() {
+        let (mut workspace, pane) = stacked();
+        workspace.panes.get_mut(pane).unwrap().confirm = Some("vim".to_string());
+        workspace.show_tab(pane, 2);
+
+        let state = workspace.panes.get(pane).unwrap();
+        assert_eq!(state.id, 2);
+        assert_eq!(state.confirm, None);
+        assert_eq!(workspace.order, [2, 9]);
+
+        // Terminals that aren't tabs of the pane are ignored.
+        workspace.show_tab(pane, 9);
+        assert_eq!(workspace.panes.get(pane).unwrap().id, 2);
+    }
+
+    #[test]
+    fn removing_the_shown_tab_shows_the_next_one() {
+        let (mut workspace, pane) = stacked();
+        workspace.show_tab(pane, 1);
+        workspace.remove_tab(pane, 1);
+        let state = workspace.panes.get(pane).unwrap();
+        assert_eq!((state.id, state.tabs.clone()), (2, vec![0, 2]));
+        assert_eq!(workspace.order, [2, 9]);
+
+        // The last tab shows the one before it instead.
+        workspace.remove_tab(pane, 2);
+        assert_eq!(workspace.panes.get(pane).unwrap().id, 0);
+        assert_eq!(workspace.order, [0, 9]);
+    }
+
+    #[test]
+    fn removing_a_hidden_tab_keeps_the_shown_one() {
+        let (mut workspace, pane) = stacked();
+        workspace.remove_tab(pane, 2);
+        let state = workspace.panes.get(pane).unwrap();
+        assert_eq!((state.id, state.tabs.clone()), (0, vec![0, 1]));
+    }
+
+    #[test]
+    fn the_last_tab_is_left_for_closing_the_pane() {
+        let (mut workspace, pane) = stacked();
+        workspace.remove_tab(pane, 1);
+        workspace.remove_tab(pane, 2);
+        workspace.remove_tab(pane, 0);
+        assert_eq!(workspace.panes.get(pane).unwrap().tabs, [0]);
+    }
+}
@@ -1,6 +1,11 @@
+pub mod modes;
+pub mod process;
+mod remote;
+
 use crate::config;
-// pub mod settings;
 use crate::terminal::actions::Action;
+use crate::terminal::backend::modes::{MouseEncodings, ScannedPty};
+use crate::terminal::backend::process::{Process, Shell};
 use crate::terminal::settings::BackendSettings;
 use alacritty_terminal::event::{Event, EventListener, Notify, OnResize, WindowSize};
 use alacritty_terminal::event_loop::{EventLoop, Msg, Notifier};
@@ -26,25 +31,35 @@ use tokio::sync::mpsc;
 #[derive(Debug, Clone)]
 pub enum BackendCommand {
     Write(Vec<u8>),
+    /// Text from the clipboard, bracketed when the program asked for it.
+    Paste(String),
     Scroll(i32),
     Resize(Option<Size<f32>>, Option<Size<f32>>),
     SelectStart(SelectionType, (f32, f32)),
     SelectUpdate((f32, f32)),
     ProcessLink(LinkAction, Point),
-    MouseReport(MouseButton, Modifiers, Point, bool),
+    /// The cell under the mouse, and its pixel position in the terminal for
+    /// programs that ask for pixels.
+    MouseReport(MouseButton, Modifiers, Point, (f32, f32), bool),
     ProcessAlacrittyEvent(Event),
 }
 
 #[derive(Debug, Clone)]
 pub enum MouseMode {
+    SgrPixels,
     Sgr,
+    Urxvt,
     Normal(bool),
 }
 
-impl From<TermMode> for MouseMode {
-    fn from(term_mode: TermMode) -> Self {
-        if term_mode.contains(TermMode::SGR_MOUSE) {
+impl MouseMode {
+    fn new(term_mode: TermMode, encodings: &MouseEncodings) -> Self {
+        if encodings.sgr_pixels() {
+            MouseMode::SgrPixels
+        } else if term_mode.contains(TermMode::SGR_MOUSE) {
             MouseMode::Sgr
+        } else if encodings.urxvt() {
+            MouseMode::Urxvt
         } else if term_mode.contains(TermMode::UTF8_MOUSE) {
             MouseMode::Normal(true)
         } else {
@@ -97,6 +112,23 @@ impl Default for TerminalSize {
     }
 }
 
+impl TerminalSize {
+    /// Lines to scroll up, or down when negative, while a selection is
+    /// dragged to `y`. Past the top or bottom edge the selection scrolls
+    /// along, faster the further the cursor is from the edge.
+    fn edge_scroll(&self, y: f32) -> i32 {
+        let cell_height = self.cell_height as f32;
+        let height = self.num_lines as f32 * cell_height;
+        if y < 0.0 {
+            (-y / cell_height).ceil() as i32
+        } else if y >= height {
+            -((y - height) / cell_height).floor() as i32 - 1
+        } else {
+            0
+        }
+    }
+}
+
 impl Dimensions for TerminalSize {
     fn total_lines(&self) -> usize {
         self.screen_lines()
@@ -130,14 +162,60 @@ impl From<TerminalSize> for WindowSize {
     }
 }
 
+/// The PTY behind a backend: either spawned by this process or owned by the daemon.
+enum Channel {
+    Local(Notifier),
+    Remote(remote::Remote),
+}
+
+impl Notify for Channel {
+    fn notify<B: Into<Cow<'static, [u8]>>>(&self, bytes: B) {
+        match self {
+            Channel::Local(notifier) => notifier.notify(bytes),
+            Channel::Remote(remote) => remote.notify(bytes),
+        }
+    }
+}
+
+impl OnResize for Channel {
+    fn on_resize(&mut self, size: WindowSize) {
+        match self {
+            Channel::Local(notifier) => notifier.on_resize(size),
+            Channel::Remote(remote) => remote.on_resize(size),
+        }
+    }
+}
+
 pub struct Backend {
     term: Arc<FairMutex<Term<EventProxy>>>,
     size: TerminalSize,
-    notifier: Notifier,
+    notifier: Channel,
+    shell: Shell,
+    mouse_encodings: Arc<MouseEncodings>,
     last_content: RenderableContent,
     pub url_regex: RegexSearch,
 }
 
+/// The terminal settings taken from frostty's config, shared with the
+/// daemon so both ends of a remote session parse its output alike.
+pub fn term_config(frostty_config: Option<config::Config>) -> term::Config {
+    let mut config = term::Config {
+        kitty_keyboard: frostty_config
+            .clone()
+            .and_then(|config| config.keyboard)
+            .and_then(|keyboard| keyboard.kitty_protocol)
+            .unwrap_or(true),
+        ..term::Config::default()
+    };
+    if let Some(separators) = frostty_config
+        .and_then(|config| config.selection)
+        .and_then(|selection| selection.semantic_separators)
+    {
+        config.semantic_escape_chars = separators;
+    }
+    config
+}
+
 impl Backend {
     pub fn new(
         id: u64,
@@ -145,19 +223,14 @@ impl Backend {
         settings: BackendSettings,
         font_size: Size<f32>,
     ) -> Result<Self> {
-        let pty_config = tty::Options {
-            shell: Some(tty::Shell::new(settings.program, settings.args)),
-            ..tty::Options::default()
-        };
-        let config = term::Config::default();
+        let config = term_config(config::Config::new());
         let terminal_size = TerminalSize {
             cell_width: font_size.width as u16,
             cell_height: font_size.height as u16,
             ..TerminalSize::default()
         };
 
-        let pty = tty::new(&pty_config, terminal_size.into(), id)?;
-        let event_proxy = EventProxy(event_sender);
+        let event_proxy = EventProxy(event_sender.clone());
 
         let mut term = Term::new(config, &terminal_size, event_proxy.clone());
         let cursor_style = term.cursor_style();
@@ -168,20 +241,50 @@ impl Backend {
             terminal_mode: *term.mode(),
             terminal_size,
             cursor: cursor.clone(),
-            cursor_style: cursor_style.clone(),
+            cursor_style,
             hovered_hyperlink: None,
         };
 
         let term = Arc::new(FairMutex::new(term));
-        let pty_event_loop = EventLoop::new(term.clone(), event_proxy, pty, false, false)?;
-        let notifier = Notifier(pty_event_loop.channel());
-        let _pty_join_handle = pty_event_loop.spawn();
+        let mouse_encodings = Arc::new(MouseEncodings::default());
+        let (notifier, shell) = match settings.session {
+            Some(session) => {
+                let (shell, pid) = Shell::remote();
+                let remote = remote::Remote::connect(
+                    &settings,
+                    session,
+                    terminal_size.into(),
+                    term.clone(),
+                    event_sender,
+                    pid,
+                    mouse_encodings.clone(),
+                )?;
+                (Channel::Remote(remote), shell)
+            }
+            None => {
+                let pty_config = tty::Options {
+                    shell: Some(tty::Shell::new(settings.program, settings.args)),
+                    working_directory: settings.working_directory,
+                    env: settings.env,
+                    ..tty::Options::default()
+                };
+                let pty = tty::new(&pty_config, terminal_size.into(), id)?;
+                let shell = Shell::local(&pty)?;
+                let pty = ScannedPty::new(pty, mouse_encodings.clone())?;
+                let pty_event_loop = EventLoop::new(term.clone(), event_proxy, pty, false, false)?;
+                let notifier = Notifier(pty_event_loop.channel());
+                let _pty_join_handle = pty_event_loop.spawn();
+                (Channel::Local(notifier), shell)
+            }
+        };
         let url_regex = RegexSearch::new(r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#).unwrap();
 
         Ok(Self {
             term: term.clone(),
             size: terminal_size,
             notifier,
+            shell,
+            mouse_encodings,
             last_content: initial_content,
             url_regex,
         })
@@ -207,6 +310,11 @@ impl Backend {
                     Event::Bell => {
                         action = Action::Bell;
                     }
+                    // Replies to queries, such as for the keyboard protocol
+                    // flags. The daemon answers for remote sessions itself.
+                    Event::PtyWrite(text) if !self.is_remote() => {
+                        self.write(text.into_bytes());
+                    }
                     _ => {}
                 };
             }
@@ -214,6 +322,10 @@ impl Backend {
                 self.write(input);
                 term.scroll_display(Scroll::Bottom);
             }
+            BackendCommand::Paste(text) => {
+                self.paste(&term, &text);
+                term.scroll_display(Scroll::Bottom);
+            }
             BackendCommand::Scroll(delta) => {
                 self.scroll(&mut term, delta);
                 self.internal_sync(&mut term);
@@ -237,8 +349,8 @@ impl Backend {
             BackendCommand::ProcessLink(link_action, point) => {
                 action = self.process_link_action(&term, link_action, point);
             }
-            BackendCommand::MouseReport(button, modifiers, point, pressed) => {
-                self.process_mouse_report(button, modifiers, point, pressed);
+            BackendCommand::MouseReport(button, modifiers, point, pixels, pressed) => {
+                self.process_mouse_report(button, modifiers, point, pixels, pressed);
                 action = Action::Redraw;
             }
         };
@@ -295,75 +407,13 @@ impl Backend {
         button: MouseButton,
         modifiers: Modifiers,
         point: Point,
+        pixels: (f32, f32),
         pressed: bool,
     ) {
-        let mut mods = 0;
-        if modifiers.contains(Modifiers::SHIFT) {
-            mods += 4;
-        }
-        if modifiers.contains(Modifiers::ALT) {
-            mods += 8;
-        }
-        if modifiers.contains(Modifiers::COMMAND) {
-            mods += 16;
-        }
-
-        match MouseMode::from(self.last_content.terminal_mode) {
-            MouseMode::Sgr => self.sgr_mouse_report(point, button as u8 + mods, pressed),
-            MouseMode::Normal(is_utf8) => {
-                if pressed {
-                    self.normal_mouse_report(point, button as u8 + mods, is_utf8)
-                } else {
-                    self.normal_mouse_report(point, 3 + mods, is_utf8)
-                }
-            }
-        }
-    }
-
-    fn sgr_mouse_report(&self, point: Point, button: u8, pressed: bool) {
-        let c = if pressed { 'M' } else { 'm' };
-
-        let msg = format!(
-            "\x1b[<{};{};{}{}",
-            button,
-            point.column + 1,
-            point.line + 1,
-            c
-        );
-
-        self.notifier.notify(msg.as_bytes().to_vec());
-    }
-
-    fn normal_mouse_report(&self, point: Point, button: u8, is_utf8: bool) {
-        let Point { line, column } = point;
-        let max_point = if is_utf8 { 2015 } else { 223 };
-
-        if line >= max_point || column >= max_point {
-            return;
-        }
-
-        let mut msg = vec![b'\x1b', b'[', b'M', 32 + button];
-
-        let mouse_pos_encode = |pos: usize| -> Vec<u8> {
-            let pos = 32 + 1 + pos;
-            let first = 0xC0 + pos / 64;
-            let second = 0x80 + (pos & 63);
-            vec![first as u8, second as u8]
-        };
-
-        if is_utf8 && column >= Column(95) {
-            msg.append(&mut mouse_pos_encode(column.0));
-        } else {
-            msg.push(32 + 1 + column.0 as u8);
-        }
-
-        if is_utf8 && line >= 95 {
-            msg.append(&mut mouse_pos_encode(line.0 as usize));
-        } else {
-            msg.push(32 + 1 + line.0 as u8);
+        let mode = MouseMode::new(self.last_content.terminal_mode, &self.mouse_encodings);
+        if let Some(report) = mouse_report(mode, button, modifiers, point, pixels, pressed) {
+            self.notifier.notify(report);
         }
-
-        self.notifier.notify(msg);
     }
 
     fn start_selection(
@@ -382,6 +432,11 @@ impl Backend {
     }
 
     fn update_selection(&mut self, terminal: &mut Term<EventProxy>, x: f32, y: f32) {
+        let lines = self.size.edge_scroll(y);
+        if lines != 0 {
+            terminal.scroll_display(Scroll::Delta(lines));
+        }
+
         let display_offset = terminal.grid().display_offset();
         if let Some(ref mut selection) = terminal.selection {
             let location = Self::selection_point(x, y, &self.size, display_offset);
@@ -389,6 +444,11 @@ impl Backend {
         }
     }
 
+    /// Whether mouse reports carry pixels, so motion within a cell counts.
+    pub fn reports_pixels(&self) -> bool {
+        self.mouse_encodings.sgr_pixels()
+    }
+
     pub fn selection_point(
         x: f32,
         y: f32,
@@ -448,6 +508,11 @@ impl Backend {
         self.notifier.notify(input);
     }
 
+    fn paste(&self, terminal: &Term<EventProxy>, text: &str) {
+        let bracketed = terminal.mode().contains(TermMode::BRACKETED_PASTE);
+        self.write(paste_input(text, bracketed));
+    }
+
     fn scroll(&mut self, terminal: &mut Term<EventProxy>, delta_value: i32) {
         if delta_value != 0 {
             let scroll_sens = config::Config::new()
@@ -475,17 +540,15 @@ impl Backend {
         }
     }
 
+    /// The selected text, including any part of it scrolled out of view,
+    /// with soft-wrapped lines joined back together.
     pub fn selectable_content(&self) -> String {
-        let content = self.renderable_content();
-        let mut result = String::new();
-        if let Some(range) = content.selectable_range {
-            for indexed in content.grid.display_iter() {
-                if range.contains(indexed.point) {
-                    result.push(indexed.c);
-                }
-            }
-        }
-        result
+        let text = self.term.lock().selection_to_string().unwrap_or_default();
+        let trim = config::Config::new()
+            .and_then(|config| config.selection)
+            .and_then(|selection| selection.trim_whitespace)
+            .unwrap_or(false);
+        if trim { trim_lines(&text) } else { text }
     }
 
     pub fn sync(&mut self) {
@@ -498,7 +561,7 @@ impl Backend {
         let term = self.term.clone();
         let term = term.lock();
 
-        self.last_content.cursor_style = term.cursor_style().clone();
+        self.last_content.cursor_style = term.cursor_style();
     }
 
     fn internal_sync(&mut self, terminal: &mut Term<EventProxy>) {
@@ -512,11 +575,34 @@ impl Backend {
         self.last_content.grid = terminal.grid().clone();
         self.last_content.selectable_range = selectable_range;
         self.last_content.cursor = cursor.clone();
-        self.last_content.cursor_style = cursor_style.clone();
+        self.last_content.cursor_style = cursor_style;
         self.last_content.terminal_mode = *terminal.mode();
         self.last_content.terminal_size = self.size;
     }
 
+    /// Ends the shell, including one kept alive by the daemon.
+    pub fn kill(&self) {
+        match &self.notifier {
+            Channel::Local(notifier) => {
+                let _ = notifier.0.send(Msg::Shutdown);
+            }
+            Channel::Remote(remote) => remote.kill(),
+        }
+    }
+
+    /// Whether the shell lives in the daemon and outlasts this backend.
+    pub fn is_remote(&self) -> bool {
+        matches!(self.notifier, Channel::Remote(_))
+    }
+
+    pub fn foreground_process(&self) -> Option<Process> {
+        self.shell.foreground()
+    }
+
+    pub fn current_process(&self) -> Option<Process> {
+        self.shell.current()
+    }
+
     pub fn renderable_content(&self) -> &RenderableContent {
         &self.last_content
     }
@@ -529,8 +615,7 @@ impl Backend {
         point: Point,
         regex: &mut RegexSearch,
     ) -> Option<Match> {
-        let x = visible_regex_match_iter(terminal, regex).find(|rm| rm.contains(&point));
-        x
+        visible_regex_match_iter(terminal, regex).find(|rm| rm.contains(&point))
     }
 }
 
@@ -551,6 +636,123 @@ fn visible_regex_match_iter<'a>(
         .skip_while(move |rm| rm.end().line < viewport_start)
         .take_while(move |rm| rm.start().line <= viewport_end)
 }
+/// The report of a mouse `button` event at `point`, or at `pixels` for SGR
+/// pixel reports, encoded the way `mode` asks. Normal reports can't reach
+/// cells past their largest coordinate and give `None` there.
+fn mouse_report(
+    mode: MouseMode,
+    button: MouseButton,
+    modifiers: Modifiers,
+    point: Point,
+    pixels: (f32, f32),
+    pressed: bool,
+) -> Option<Vec<u8>> {
+    let mut mods = 0;
+    if modifiers.contains(Modifiers::SHIFT) {
+        mods += 4;
+    }
+    if modifiers.contains(Modifiers::ALT) {
+        mods += 8;
+    }
+    if modifiers.contains(Modifiers::COMMAND) {
+        mods += 16;
+    }
+
+    match mode {
+        MouseMode::SgrPixels => {
+            let (x, y) = pixels;
+            let point = Point::new(Line(y.max(0.0) as i32), Column(x.max(0.0) as usize));
+            Some(sgr_mouse_report(point, button as u8 + mods, pressed))
+        }
+        MouseMode::Sgr => Some(sgr_mouse_report(point, button as u8 + mods, pressed)),
+        MouseMode::Urxvt => {
+            let button = if pressed { button as u8 } else { 3 };
+            Some(urxvt_mouse_report(point, button + mods))
+        }
+        MouseMode::Normal(is_utf8) => {
+            let button = if pressed { button as u8 } else { 3 };
+            normal_mouse_report(point, button + mods, is_utf8)
+        }
+    }
+}
+
+fn sgr_mouse_report(point: Point, button: u8, pressed: bool) -> Vec<u8> {
+    let c = if pressed { 'M' } else { 'm' };
+
+    let msg = format!(
+        "\x1b[<{};{};{}{}",
+        button,
+        point.column + 1,
+        point.line + 1,
+        c
+    );
+
+    msg.into_bytes()
+}
+
+fn urxvt_mouse_report(point: Point, button: u8) -> Vec<u8> {
+    let msg = format!(
+        "\x1b[{};{};{}M",
+        32 + button,
+        point.column + 1,
+        point.line + 1
+    );
+
+    msg.into_bytes()
+}
+
+fn normal_mouse_report(point: Point, button: u8, is_utf8: bool) -> Option<Vec<u8>> {
+    let Point { line, column } = point;
+    let max_point = if is_utf8 { 2015 } else { 223 };
+
+    if line >= max_point || column >= max_point {
+        return None;
+    }
+
+    let mut msg = vec![b'\x1b', b'[', b'M', 32 + button];
+
+    let mouse_pos_encode = |pos: usize| -> Vec<u8> {
+        let pos = 32 + 1 + pos;
+        let first = 0xC0 + pos / 64;
+        let second = 0x80 + (pos & 63);
+        vec![first as u8, second as u8]
+    };
+
+    if is_utf8 && column >= Column(95) {
+        msg.append(&mut mouse_pos_encode(column.0));
+    } else {
+        msg.push(32 + 1 + column.0 as u8);
+    }
+
+    if is_utf8 && line >= 95 {
+        msg.append(&mut mouse_pos_encode(line.0 as usize));
+    } else {
+        msg.push(32 + 1 + line.0 as u8);
+    }
+
+    Some(msg)
+}
+
+/// `text` without whitespace at the end of its lines.
+fn trim_lines(text: &str) -> String {
+    text.lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The input that pastes `text`, inside brackets when the program asked for
+/// bracketed paste and otherwise with line breaks typed as Enter.
+fn paste_input(text: &str, bracketed: bool) -> Vec<u8> {
+    if bracketed {
+        // Without escapes the paste can't end the bracket early, and
+        // without ETX it can't interrupt the program reading it.
+        let text = text.replace(['\x1b', '\x03'], "");
+        format!("\x1b[200~{}\x1b[201~", text).into_bytes()
+    } else {
+        text.replace("\r\n", "\r").replace('\n', "\r").into_bytes()
+    }
+}
 
 pub struct RenderableContent {
     pub grid: Grid<Cell>,
@@ -578,7 +780,10 @@ impl Default for RenderableContent {
 
 impl Drop for Backend {
     fn drop(&mut self) {
-        let _ = self.notifier.0.send(Msg::Shutdown);
+        // Dropping a remote backend only detaches from its daemon session.
+        if let Channel::Local(notifier) = &self.notifier {
+            let _ = notifier.0.send(Msg::Shutdown);
+        }
     }
 }
 
@@ -590,3 +795,186 @@ impl EventListener for EventProxy {
         let _ = self.0.blocking_send(event);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bracketed_pastes_cannot_escape_the_bracket() {
+        assert_eq!(
+            paste_input("ls\x1b[201~; rm -rf ~\x03\n", true),
+            b"\x1b[200~ls[201~; rm -rf ~\n\x1b[201~"
+        );
+    }
+
+    /// Five lines of ten by twenty pixel cells.
+    fn size() -> TerminalSize {
+        TerminalSize {
+            cell_width: 10,
+            cell_height: 20,
+            num_cols: 8,
+            num_lines: 5,
+            layout_width: 80.0,
+            layout_height: 100.0,
+        }
+    }
+
+    #[test]
+    fn dragging_past_an_edge_scrolls_faster_further_out() {
+        let size = size();
+        assert_eq!(size.edge_scroll(0.0), 0);
+        assert_eq!(size.edge_scroll(99.0), 0);
+        assert_eq!(size.edge_scroll(-1.0), 1);
+        assert_eq!(size.edge_scroll(-41.0), 3);
+        assert_eq!(size.edge_scroll(100.0), -1);
+        assert_eq!(size.edge_scroll(145.0), -3);
+    }
+
+    #[test]
+    fn selections_reach_into_the_scrollback() {
+        let size = size();
+        let point = Backend::selection_point(-30.0, -30.0, &size, 0);
+        assert_eq!(point, Point::new(Line(0), Column(0)));
+        let point = Backend::selection_point(35.0, 50.0, &size, 10);
+        assert_eq!(point, Point::new(Line(-8), Column(3)));
+        // Past the bottom right corner points stay on the screen.
+        let point = Backend::selection_point(500.0, 500.0, &size, 0);
+        assert_eq!(point, Point::new(Line(4), Column(7)));
+    }
+
+    fn report(
+        mode: MouseMode,
+        button: MouseButton,
+        modifiers: Modifiers,
+        pressed: bool,
+    ) -> Vec<u8> {
+        let point = Point::new(Line(4), Column(9));
+        mouse_report(mode, button, modifiers, point, (95.0, 47.0), pressed).unwrap()
+    }
+
+    #[test]
+    fn normal_reports_encode_buttons_and_cells() {
+        let normal = || MouseMode::Normal(false);
+        let empty = Modifiers::empty();
+        assert_eq!(
+            report(normal(), MouseButton::LeftButton, empty, true),
+            b"\x1b[M *%"
+        );
+        // Releases don't say which button.
+        assert_eq!(
+            report(normal(), MouseButton::RightButton, empty, false),
+            b"\x1b[M#*%"
+        );
+        assert_eq!(
+            report(normal(), MouseButton::ScrollDown, empty, true),
+            b"\x1b[Ma*%"
+        );
+        assert_eq!(
+            report(normal(), MouseButton::LeftMove, empty, true),
+            b"\x1b[M@*%"
+        );
+    }
+
+    #[test]
+    fn modifiers_are_added_to_the_button() {
+        let sgr = || MouseMode::Sgr;
+        let button = MouseButton::MiddleButton;
+        assert_eq!(
+            report(sgr(), button.clone(), Modifiers::SHIFT, true),
+            b"\x1b[<5;10;5M"
+        );
+        assert_eq!(
+            report(sgr(), button.clone(), Modifiers::ALT, true),
+            b"\x1b[<9;10;5M"
+        );
+        assert_eq!(
+            report(sgr(), button, Modifiers::CTRL | Modifiers::ALT, false),
+            b"\x1b[<25;10;5m"
+        );
+    }
+
+    #[test]
+    fn utf8_reports_reach_past_column_223() {
+        let point = Point::new(Line(0), Column(300));
+        let button = || MouseButton::LeftButton;
+        let normal = mouse_report(
+            MouseMode::Normal(false),
+            button(),
+            Modifiers::empty(),
+            point,
+            (0.0, 0.0),
+            true,
+        );
+        assert_eq!(normal, None);
+        let utf8 = mouse_report(
+            MouseMode::Normal(true),
+            button(),
+            Modifiers::empty(),
+            point,
+            (0.0, 0.0),
+            true,
+        );
+        assert_eq!(utf8.unwrap(), "\x1b[M \u{14d}!".as_bytes());
+    }
+
+    #[test]
+    fn urxvt_and_sgr_pixel_reports() {
+        let empty = Modifiers::empty();
+        assert_eq!(
+            report(MouseMode::Urxvt, MouseButton::LeftButton, empty, true),
+            b"\x1b[32;10;5M"
+        );
+        assert_eq!(
+            report(MouseMode::Urxvt, MouseButton::LeftButton, empty, false),
+            b"\x1b[35;10;5M"
+        );
+        assert_eq!(
+            report(MouseMode::SgrPixels, MouseButton::RightButton, empty, false),
+            b"\x1b[<2;96;48m"
+        );
+    }
+
+    #[test]
+    fn the_richest_encoding_the_program_asked_for_wins() {
+        let mode = |setup: &str, term_mode| {
+            let encodings = Arc::new(MouseEncodings::default());
+            modes::Scanner::new(encodings.clone()).advance(setup.as_bytes());
+            MouseMode::new(term_mode, &encodings)
+        };
+        let sgr = TermMode::SGR_MOUSE;
+        let utf8 = TermMode::UTF8_MOUSE;
+        assert!(matches!(mode("\x1b[?1016h", sgr), MouseMode::SgrPixels));
+        assert!(matches!(mode("\x1b[?1015h", sgr), MouseMode::Sgr));
+        assert!(matches!(mode("\x1b[?1015h", utf8), MouseMode::Urxvt));
+        assert!(matches!(mode("", utf8), MouseMode::Normal(true)));
+        assert!(matches!(
+            mode("\x1b[?1016h\x1b[?1016l", TermMode::empty()),
+            MouseMode::Normal(false)
+        ));
+    }
+
+    #[test]
+    fn trimming_drops_trailing_whitespace_from_every_line() {
+        assert_eq!(
+            trim_lines("ls -l   \n\t total 4\t\n\n"),
+            "ls -l\n\t total 4\n"
+        );
+    }
+
+    #[test]
+    fn the_config_sets_the_semantic_separators() {
+        let config = toml::from_str("[selection]\nsemantic_separators = \" ,:\"").unwrap();
+        assert_eq!(term_config(Some(config)).semantic_escape_chars, " ,:");
+        assert_eq!(
+            term_config(None).semantic_escape_chars,
+            term::Config::default().semantic_escape_chars
+        );
+    }
+
+    #[test]
+    fn plain_pastes_type_line_breaks_as_enter() {
+        assert_eq!(paste_input("a\r\nb\nc\rd", false), b"a\rb\rc\rd");
+        assert_eq!(paste_input("\x1b", false), b"\x1b");
+    }
+}
@@ -0,0 +1,180 @@
+//! Tracks the private modes that alacritty_terminal parses but ignores, by
+//! running the PTY output through a second parser.
+
+use alacritty_terminal::event::{OnResize, WindowSize};
+use alacritty_terminal::tty::{ChildEvent, EventedPty, EventedReadWrite, Pty};
+use alacritty_terminal::vte::ansi::{Handler, PrivateMode, Processor};
+use polling::{Event, PollMode, Poller};
+use std::fs::File;
+use std::io::{Read, Result};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const URXVT_MOUSE: u16 = 1015;
+const SGR_PIXELS_MOUSE: u16 = 1016;
+
+/// Mouse encodings set by the program in the terminal.
+#[derive(Debug, Default)]
+pub struct MouseEncodings {
+    urxvt: AtomicBool,
+    sgr_pixels: AtomicBool,
+}
+
+impl MouseEncodings {
+    pub fn urxvt(&self) -> bool {
+        self.urxvt.load(Ordering::Relaxed)
+    }
+
+    pub fn sgr_pixels(&self) -> bool {
+        self.sgr_pixels.load(Ordering::Relaxed)
+    }
+
+    fn set(&self, mode: PrivateMode, enabled: bool) {
+        match mode {
+            PrivateMode::Unknown(URXVT_MOUSE) => self.urxvt.store(enabled, Ordering::Relaxed),
+            PrivateMode::Unknown(SGR_PIXELS_MOUSE) => {
+                self.sgr_pixels.store(enabled, Ordering::Relaxed)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Feeds output to a parser that only listens for mode switches.
+pub struct Scanner {
+    processor: Processor,
+    modes: ModeHandler,
+}
+
+struct ModeHandler(Arc<MouseEncodings>);
+
+impl Handler for ModeHandler {
+    fn set_private_mode(&mut self, mode: PrivateMode) {
+        self.0.set(mode, true);
+    }
+
+    fn unset_private_mode(&mut self, mode: PrivateMode) {
+        self.0.set(mode, false);
+    }
+
+    fn reset_state(&mut self) {
+        self.0.urxvt.store(false, Ordering::Relaxed);
+        self.0.sgr_pixels.store(false, Ordering::Relaxed);
+    }
+}
+
+impl Scanner {
+    pub fn new(encodings: Arc<MouseEncodings>) -> Self {
+        Self {
+            processor: Processor::new(),
+            modes: ModeHandler(encodings),
+        }
+    }
+
+    pub fn advance(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.processor.advance(&mut self.modes, *byte);
+        }
+    }
+}
+
+/// The reading end of a PTY, scanned on its way to the terminal.
+pub struct ScannedReader {
+    file: File,
+    scanner: Scanner,
+}
+
+impl Read for ScannedReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let read = self.file.read(buf)?;
+        self.scanner.advance(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// A local PTY whose output is scanned for the modes in [`MouseEncodings`].
+pub struct ScannedPty {
+    pty: Pty,
+    reader: ScannedReader,
+}
+
+impl ScannedPty {
+    pub fn new(pty: Pty, encodings: Arc<MouseEncodings>) -> Result<Self> {
+        Ok(Self {
+            reader: ScannedReader {
+                file: pty.file().try_clone()?,
+                scanner: Scanner::new(encodings),
+            },
+            pty,
+        })
+    }
+}
+
+impl EventedReadWrite for ScannedPty {
+    type Reader = ScannedReader;
+    type Writer = File;
+
+    unsafe fn register(&mut self, poll: &Arc<Poller>, event: Event, mode: PollMode) -> Result<()> {
+        unsafe { self.pty.register(poll, event, mode) }
+    }
+
+    fn reregister(&mut self, poll: &Arc<Poller>, event: Event, mode: PollMode) -> Result<()> {
+        self.pty.reregister(poll, event, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> Result<()> {
+        self.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut ScannedReader {
+        &mut self.reader
+    }
+
+    fn writer(&mut self) -> &mut File {
+        self.pty.writer()
+    }
+}
+
+impl EventedPty for ScannedPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl OnResize for ScannedPty {
+    fn on_resize(&mut self, size: WindowSize) {
+        self.pty.on_resize(size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(output: &str) -> Arc<MouseEncodings> {
+        let encodings = Arc::new(MouseEncodings::default());
+        Scanner::new(encodings.clone()).advance(output.as_bytes());
+        encodings
+    }
+
+    #[test]
+    fn programs_switch_the_encodings_on_and_off() {
+        let encodings = scan("\x1b[?1015h\x1b[?1016h");
+        assert!(encodings.urxvt() && encodings.sgr_pixels());
+        let encodings = scan("\x1b[?1015h\x1b[?1016h\x1b[?1015;1016l");
+        assert!(!encodings.urxvt() && !encodings.sgr_pixels());
+    }
+
+    #[test]
+    fn other_output_leaves_them_alone() {
+        let encodings = scan("\x1b[?1016hls\r\n\x1b[?1006l\x1b[31mhello");
+        assert!(encodings.sgr_pixels());
+        assert!(!encodings.urxvt());
+    }
+
+    #[test]
+    fn a_reset_turns_them_off() {
+        let encodings = scan("\x1b[?1015h\x1b[?1016h\x1bc");
+        assert!(!encodings.urxvt() && !encodings.sgr_pixels());
+    }
+}
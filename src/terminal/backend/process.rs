@@ -0,0 +1,117 @@
+//! Looks up the processes running inside a terminal through `/proc`.
+
+use alacritty_terminal::tty::Pty;
+use std::fs::{self, File};
+use std::io::Result;
+use std::os::fd::AsRawFd;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// A process running in a terminal.
+#[derive(Debug, Clone)]
+pub struct Process {
+    pub pid: i32,
+    /// The executable name the kernel reports, at most 15 bytes long.
+    pub name: String,
+    /// The full command line, or `name` for processes that hide it.
+    pub command: String,
+    pub cwd: Option<PathBuf>,
+}
+
+impl Process {
+    fn read(pid: i32) -> Option<Self> {
+        let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        let name = stat[stat.find('(')? + 1..stat.rfind(')')?].to_string();
+        let cmdline = fs::read(format!("/proc/{}/cmdline", pid)).unwrap_or_default();
+        let command = cmdline
+            .split(|byte| *byte == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(String::from_utf8_lossy)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Some(Self {
+            pid,
+            cwd: fs::read_link(format!("/proc/{}/cwd", pid)).ok(),
+            command: if command.is_empty() {
+                name.clone()
+            } else {
+                command
+            },
+            name,
+        })
+    }
+}
+
+/// The shell at the root of a terminal's PTY.
+pub struct Shell {
+    /// Zero until a daemon session reports its shell.
+    pid: Arc<AtomicU32>,
+    /// Our own handle on the PTY master, for local shells.
+    master: Option<File>,
+}
+
+impl Shell {
+    pub fn local(pty: &Pty) -> Result<Self> {
+        Ok(Self {
+            pid: Arc::new(AtomicU32::new(pty.child().id())),
+            master: Some(pty.file().try_clone()?),
+        })
+    }
+
+    /// A shell owned by the daemon, whose pid is stored into the returned
+    /// handle once the session is attached.
+    pub fn remote() -> (Self, Arc<AtomicU32>) {
+        let pid = Arc::new(AtomicU32::new(0));
+        let shell = Self {
+            pid: pid.clone(),
+            master: None,
+        };
+        (shell, pid)
+    }
+
+    fn pid(&self) -> Option<i32> {
+        match self.pid.load(Ordering::Relaxed) {
+            0 => None,
+            pid => Some(pid as i32),
+        }
+    }
+
+    fn foreground_group(&self, pid: i32) -> Option<i32> {
+        let group = match &self.master {
+            Some(master) => unsafe { libc::tcgetpgrp(master.as_raw_fd()) },
+            // The daemon holds the master, but the shell's stat names the
+            // foreground group of its terminal all the same.
+            None => foreground_group(pid)?,
+        };
+        (group > 0).then_some(group)
+    }
+
+    /// Leader of the process group in the foreground of the PTY, unless that
+    /// is the shell itself.
+    pub fn foreground(&self) -> Option<Process> {
+        let pid = self.pid()?;
+        let group = self.foreground_group(pid)?;
+        if group == pid {
+            return None;
+        }
+        Process::read(group)
+    }
+
+    /// Leader of the process group in the foreground of the PTY, falling back
+    /// to the shell.
+    pub fn current(&self) -> Option<Process> {
+        let pid = self.pid()?;
+        self.foreground_group(pid)
+            .and_then(Process::read)
+            .or_else(|| Process::read(pid))
+    }
+}
+
+/// The `tpgid` field of `/proc/<pid>/stat`.
+fn foreground_group(pid: i32) -> Option<i32> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // Fields after the command name: state, ppid, pgrp, session, tty_nr, tpgid.
+    stat[stat.rfind(')')? + 2..].split(' ').nth(5)?.parse().ok()
+}
@@ -0,0 +1,101 @@
+use crate::daemon::{self, protocol::Reply, protocol::Request};
+use crate::terminal::backend::modes::{MouseEncodings, Scanner};
+use crate::terminal::settings::{BackendSettings, Session};
+use alacritty_terminal::event::{Event, EventListener, Notify, OnResize, WindowSize};
+use alacritty_terminal::sync::FairMutex;
+use alacritty_terminal::term::Term;
+use alacritty_terminal::vte::ansi::Processor;
+use std::borrow::Cow;
+use std::io::Result;
+use std::net::Shutdown;
+use std::os::unix::net::UnixStream;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use tokio::sync::mpsc;
+
+/// A terminal whose PTY is owned by `frostty --daemon`. Output streamed by
+/// the daemon is replayed into a local `Term` for rendering.
+pub struct Remote {
+    stream: UnixStream,
+}
+
+impl Remote {
+    pub fn connect<T: EventListener + Send + 'static>(
+        settings: &BackendSettings,
+        session: Session,
+        size: WindowSize,
+        term: Arc<FairMutex<Term<T>>>,
+        event_sender: mpsc::Sender<Event>,
+        pid: Arc<AtomicU32>,
+        mouse_encodings: Arc<MouseEncodings>,
+    ) -> Result<Self> {
+        let stream = daemon::connect()?;
+        let request = match session {
+            Session::Create { workspace } => Request::Create {
+                workspace,
+                size,
+                program: settings.program.clone(),
+                args: settings.args.clone(),
+                cwd: settings
+                    .working_directory
+                    .as_ref()
+                    .map(|cwd| cwd.to_string_lossy().into_owned()),
+                env: settings.env.clone().into_iter().collect(),
+            },
+            Session::Attach(session) => Request::Attach { session, size },
+        };
+        request.write_to(&mut &stream)?;
+
+        let mut reader = stream.try_clone()?;
+        thread::spawn(move || {
+            let mut processor: Processor = Processor::new();
+            let mut scanner = Scanner::new(mouse_encodings);
+            while let Ok(reply) = Reply::read_from(&mut reader) {
+                match reply {
+                    Reply::Output(bytes) => {
+                        scanner.advance(&bytes);
+                        let mut term = term.lock();
+                        for byte in bytes {
+                            processor.advance(&mut *term, byte);
+                        }
+                        drop(term);
+                        let _ = event_sender.blocking_send(Event::Wakeup);
+                    }
+                    Reply::Exit => break,
+                    Reply::Attached { pid: shell, .. } => pid.store(shell, Ordering::Relaxed),
+                    Reply::Sessions(_) => {}
+                }
+            }
+            let _ = event_sender.blocking_send(Event::Exit);
+        });
+
+        Ok(Self { stream })
+    }
+
+    /// Ends the session on the daemon rather than just detaching from it.
+    pub fn kill(&self) {
+        let _ = Request::Kill.write_to(&mut &self.stream);
+    }
+}
+
+impl Notify for Remote {
+    fn notify<B: Into<Cow<'static, [u8]>>>(&self, bytes: B) {
+        let bytes = bytes.into();
+        if !bytes.is_empty() {
+            let _ = Request::Input(bytes.into_owned()).write_to(&mut &self.stream);
+        }
+    }
+}
+
+impl OnResize for Remote {
+    fn on_resize(&mut self, size: WindowSize) {
+        let _ = Request::Resize(size).write_to(&mut &self.stream);
+    }
+}
+
+impl Drop for Remote {
+    fn drop(&mut self) {
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
@@ -8,9 +8,13 @@ use iced_core::{
 pub enum BindingAction {
     Copy,
     Paste,
+    CopyPrimary,
+    PastePrimary,
     Char(char),
     Esc(String),
     LinkOpen,
+    /// One of frostty's own hotkeys, kept from the terminal.
+    Hotkey,
     Ignore,
 }
 
@@ -18,6 +22,9 @@ pub enum BindingAction {
 pub enum InputKind {
     Char(String),
     KeyCode(Named),
+    /// A key on the numeric keypad, by the character it types, with Enter
+    /// as `"\r"`.
+    Keypad(String),
     Mouse(Button),
 }
 
@@ -31,6 +38,7 @@ pub struct Binding<T> {
 
 pub type KeyboardBinding = Binding<InputKind>;
 pub type MouseBinding = Binding<InputKind>;
+pub type KeypadBinding = Binding<InputKind>;
 
 #[macro_export]
 macro_rules! generate_bindings {
@@ -55,6 +63,9 @@ macro_rules! generate_bindings {
             (KeyboardBinding, $expr:expr) => {{
                 InputKind::Char($expr.to_string())
             }};
+            (KeypadBinding, $expr:expr) => {{
+                InputKind::Keypad($expr.to_string())
+            }};
         }
 
         let mut v = Vec::new();
@@ -81,6 +92,21 @@ macro_rules! generate_bindings {
     }};
 }
 
+/// Parses modifiers joined with `+`, such as `"alt"` or `"super+shift"`.
+pub fn parse_modifiers(text: &str) -> Option<Modifiers> {
+    text.split('+')
+        .try_fold(Modifiers::empty(), |modifiers, name| {
+            let modifier = match name.trim().to_lowercase().as_str() {
+                "alt" | "option" => Modifiers::ALT,
+                "ctrl" | "control" => Modifiers::CTRL,
+                "shift" => Modifiers::SHIFT,
+                "super" | "logo" | "command" => Modifiers::LOGO,
+                _ => return None,
+            };
+            Some(modifiers | modifier)
+        })
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct BindingsLayout {
     layout: Vec<(Binding<InputKind>, BindingAction)>,
@@ -94,9 +120,12 @@ impl Default for BindingsLayout {
 
 impl BindingsLayout {
     pub(crate) fn new() -> Self {
+        // The keys listed by hand take the place of their generated encodings
         let mut layout = Self {
-            layout: default_keyboard_bindings(),
+            layout: modified_key_bindings(),
         };
+        layout.add_bindings(default_keyboard_bindings());
+        layout.add_bindings(keypad_bindings());
         layout.add_bindings(platform_keyboard_bindings());
         layout.add_bindings(mouse_default_bindings());
         layout
@@ -188,23 +217,6 @@ fn default_keyboard_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
         ArrowDown,  Modifiers::COMMAND; BindingAction::Esc("\x1b[1;5B".into());
         ArrowLeft,  Modifiers::COMMAND; BindingAction::Esc("\x1b[1;5D".into());
         ArrowRight, Modifiers::COMMAND; BindingAction::Esc("\x1b[1;5C".into());
-        End,        Modifiers::CTRL; BindingAction::Esc("\x1b[1;5F".into());
-        Home,       Modifiers::CTRL; BindingAction::Esc("\x1b[1;5H".into());
-        Delete,     Modifiers::CTRL; BindingAction::Esc("\x1b[3;5~".into());
-        PageUp,     Modifiers::CTRL; BindingAction::Esc("\x1b[5;5~".into());
-        PageDown,   Modifiers::CTRL; BindingAction::Esc("\x1b[6;5~".into());
-        F1,         Modifiers::CTRL; BindingAction::Esc("\x1bO;5P".into());
-        F2,         Modifiers::CTRL; BindingAction::Esc("\x1bO;5Q".into());
-        F3,         Modifiers::CTRL; BindingAction::Esc("\x1bO;5R".into());
-        F4,         Modifiers::CTRL; BindingAction::Esc("\x1bO;5S".into());
-        F5,         Modifiers::CTRL; BindingAction::Esc("\x1b[15;5~".into());
-        F6,         Modifiers::CTRL; BindingAction::Esc("\x1b[17;5~".into());
-        F7,         Modifiers::CTRL; BindingAction::Esc("\x1b[18;5~".into());
-        F8,         Modifiers::CTRL; BindingAction::Esc("\x1b[19;5~".into());
-        F9,         Modifiers::CTRL; BindingAction::Esc("\x1b[20;5~".into());
-        F10,        Modifiers::CTRL; BindingAction::Esc("\x1b[21;5~".into());
-        F11,        Modifiers::CTRL; BindingAction::Esc("\x1b[23;5~".into());
-        F12,        Modifiers::CTRL; BindingAction::Esc("\x1b[24;5~".into());
         "a",        Modifiers::CTRL; BindingAction::Char('\x01');
         "b",        Modifiers::CTRL; BindingAction::Char('\x02');
         "c",        Modifiers::CTRL; BindingAction::Char('\x03');
@@ -225,7 +237,7 @@ fn default_keyboard_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
         "r",        Modifiers::CTRL; BindingAction::Char('\x12');
         "s",        Modifiers::CTRL; BindingAction::Char('\x13');
         "t",        Modifiers::CTRL; BindingAction::Char('\x14');
-        "u",        Modifiers::CTRL; BindingAction::Char('\x51');
+        "u",        Modifiers::CTRL; BindingAction::Char('\x15');
         "v",        Modifiers::CTRL; BindingAction::Char('\x16');
         "w",        Modifiers::CTRL; BindingAction::Char('\x17');
         "x",        Modifiers::CTRL; BindingAction::Char('\x18');
@@ -239,41 +251,11 @@ fn default_keyboard_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
         Enter,      Modifiers::SHIFT; BindingAction::Char('\x0d');
         Backspace,  Modifiers::SHIFT; BindingAction::Char('\x7f');
         Tab,        Modifiers::SHIFT; BindingAction::Esc("\x1b[Z".into());
-        End,        Modifiers::SHIFT, +TermMode::ALT_SCREEN; BindingAction::Esc("\x1b[1;2F".into());
-        Home,       Modifiers::SHIFT, +TermMode::ALT_SCREEN; BindingAction::Esc("\x1b[1;2H".into());
-        PageUp,     Modifiers::SHIFT, +TermMode::ALT_SCREEN; BindingAction::Esc("\x1b[5;2~".into());
-        PageDown,   Modifiers::SHIFT, +TermMode::ALT_SCREEN; BindingAction::Esc("\x1b[6;2~".into());
-        ArrowUp,    Modifiers::SHIFT; BindingAction::Esc("\x1b[1;2A".into());
-        ArrowDown,  Modifiers::SHIFT; BindingAction::Esc("\x1b[1;2B".into());
-        ArrowLeft,  Modifiers::SHIFT; BindingAction::Esc("\x1b[1;2D".into());
-        ArrowRight, Modifiers::SHIFT; BindingAction::Esc("\x1b[1;2C".into());
+        Insert,     Modifiers::SHIFT; BindingAction::PastePrimary;
         // ALT
         Backspace,  Modifiers::ALT; BindingAction::Esc("\x1b\x7f".into());
-        End,        Modifiers::ALT; BindingAction::Esc("\x1b[1;3F".into());
-        Home,       Modifiers::ALT; BindingAction::Esc("\x1b[1;3H".into());
-        Insert,     Modifiers::ALT; BindingAction::Esc("\x1b[3;2~".into());
-        Delete,     Modifiers::ALT; BindingAction::Esc("\x1b[3;3~".into());
-        PageUp,     Modifiers::ALT; BindingAction::Esc("\x1b[5;3~".into());
-        PageDown,   Modifiers::ALT; BindingAction::Esc("\x1b[6;3~".into());
-        ArrowUp,    Modifiers::ALT; BindingAction::Esc("\x1b[1;3A".into());
-        ArrowDown,  Modifiers::ALT; BindingAction::Esc("\x1b[1;3B".into());
-        ArrowLeft,  Modifiers::ALT; BindingAction::Esc("\x1b[1;3D".into());
-        ArrowRight, Modifiers::ALT; BindingAction::Esc("\x1b[1;3C".into());
-        // SHIFT + ALT
-        End,        Modifiers::SHIFT | Modifiers::ALT; BindingAction::Esc("\x1b[1;4F".into());
-        Home,       Modifiers::SHIFT | Modifiers::ALT; BindingAction::Esc("\x1b[1;4H".into());
-        ArrowUp,    Modifiers::SHIFT | Modifiers::ALT; BindingAction::Esc("\x1b[1;4A".into());
-        ArrowDown,  Modifiers::SHIFT | Modifiers::ALT; BindingAction::Esc("\x1b[1;4B".into());
-        ArrowLeft,  Modifiers::SHIFT | Modifiers::ALT; BindingAction::Esc("\x1b[1;4D".into());
-        ArrowRight, Modifiers::SHIFT | Modifiers::ALT; BindingAction::Esc("\x1b[1;4C".into());
         // SHIFT + CTRL
-        // unbound all for now since I want to reserve for appplication level bindings
-        //End,        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Esc("\x1b[1;6F".into());
-        //Home,       Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Esc("\x1b[1;6H".into());
-        //ArrowUp,    Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Esc("\x1b[1;6A".into());
-        //ArrowDown,  Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Esc("\x1b[1;6B".into());
-        //ArrowLeft,  Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Esc("\x1b[1;6D".into());
-        //ArrowRight, Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Esc("\x1b[1;6C".into());
+        Insert,     Modifiers::SHIFT | Modifiers::CTRL; BindingAction::CopyPrimary;
         "a",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x01');
         "b",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x02');
         "c",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x03');
@@ -294,7 +276,7 @@ fn default_keyboard_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
         "r",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x12');
         "s",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x13');
         "t",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x14');
-        "u",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x51');
+        "u",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x15');
         "v",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x16');
         "w",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x17');
         "x",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x18');
@@ -303,22 +285,126 @@ fn default_keyboard_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
         "2",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x00'); // Null vt100
         "6",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x1e');
         "_",        Modifiers::SHIFT | Modifiers::CTRL; BindingAction::Char('\x1f');
-        // CTRL + ALT
-        End,        Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;7F".into());
-        Home,       Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;7H".into());
-        PageUp,     Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[5;7~".into());
-        PageDown,   Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[6;7~".into());
-        ArrowUp,    Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;7A".into());
-        ArrowDown,  Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;7B".into());
-        ArrowLeft,  Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;7D".into());
-        ArrowRight, Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;7C".into());
-        // SHIFT + CTRL + ALT
-        End,        Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;8F".into());
-        Home,       Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;8H".into());
-        ArrowUp,    Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;8A".into());
-        ArrowDown,  Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;8B".into());
-        ArrowLeft,  Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;8D".into());
-        ArrowRight, Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT; BindingAction::Esc("\x1b[1;8C".into());
+    )
+}
+
+/// Named keys that take an xterm modifier parameter, with the number it
+/// follows and the final character of the sequence.
+const MODIFIABLE_KEYS: [(Named, u8, char); 30] = [
+    (Named::ArrowUp, 1, 'A'),
+    (Named::ArrowDown, 1, 'B'),
+    (Named::ArrowRight, 1, 'C'),
+    (Named::ArrowLeft, 1, 'D'),
+    (Named::End, 1, 'F'),
+    (Named::Home, 1, 'H'),
+    (Named::Insert, 2, '~'),
+    (Named::Delete, 3, '~'),
+    (Named::PageUp, 5, '~'),
+    (Named::PageDown, 6, '~'),
+    (Named::F1, 1, 'P'),
+    (Named::F2, 1, 'Q'),
+    (Named::F3, 1, 'R'),
+    (Named::F4, 1, 'S'),
+    (Named::F5, 15, '~'),
+    (Named::F6, 17, '~'),
+    (Named::F7, 18, '~'),
+    (Named::F8, 19, '~'),
+    (Named::F9, 20, '~'),
+    (Named::F10, 21, '~'),
+    (Named::F11, 23, '~'),
+    (Named::F12, 24, '~'),
+    (Named::F13, 25, '~'),
+    (Named::F14, 26, '~'),
+    (Named::F15, 28, '~'),
+    (Named::F16, 29, '~'),
+    (Named::F17, 31, '~'),
+    (Named::F18, 32, '~'),
+    (Named::F19, 33, '~'),
+    (Named::F20, 34, '~'),
+];
+
+/// The xterm encodings of named keys held with any mix of SHIFT, ALT and
+/// CTRL, where the parameter is one more than the modifier bits.
+fn modified_key_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
+    let mut bindings = Vec::new();
+    for (key, number, terminator) in MODIFIABLE_KEYS {
+        for bits in 1..8 {
+            let mut modifiers = Modifiers::empty();
+            modifiers.set(Modifiers::SHIFT, bits & 1 != 0);
+            modifiers.set(Modifiers::ALT, bits & 2 != 0);
+            modifiers.set(Modifiers::CTRL, bits & 4 != 0);
+
+            let is_cursor_key = number == 1 && matches!(terminator, 'A'..='H');
+            // SHIFT + CTRL on the cursor keys is left for application level bindings
+            if is_cursor_key && modifiers == Modifiers::SHIFT | Modifiers::CTRL {
+                continue;
+            }
+
+            // SHIFT + paging keys only reach programs on the alternate screen
+            let mut terminal_mode_include = TermMode::empty();
+            if modifiers == Modifiers::SHIFT
+                && matches!(
+                    key,
+                    Named::Home | Named::End | Named::PageUp | Named::PageDown
+                )
+            {
+                terminal_mode_include = TermMode::ALT_SCREEN;
+            }
+
+            let binding = KeyboardBinding {
+                target: InputKind::KeyCode(key),
+                modifiers,
+                terminal_mode_include,
+                terminal_mode_exclude: TermMode::empty(),
+            };
+            let seq = format!("\x1b[{};{}{}", number, bits + 1, terminator);
+            bindings.push((binding, BindingAction::Esc(seq)));
+        }
+    }
+    bindings
+}
+
+fn keypad_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
+    generate_bindings!(
+        KeypadBinding;
+        // APP_KEYPAD Excluding
+        "0",  ~TermMode::APP_KEYPAD; BindingAction::Char('0');
+        "1",  ~TermMode::APP_KEYPAD; BindingAction::Char('1');
+        "2",  ~TermMode::APP_KEYPAD; BindingAction::Char('2');
+        "3",  ~TermMode::APP_KEYPAD; BindingAction::Char('3');
+        "4",  ~TermMode::APP_KEYPAD; BindingAction::Char('4');
+        "5",  ~TermMode::APP_KEYPAD; BindingAction::Char('5');
+        "6",  ~TermMode::APP_KEYPAD; BindingAction::Char('6');
+        "7",  ~TermMode::APP_KEYPAD; BindingAction::Char('7');
+        "8",  ~TermMode::APP_KEYPAD; BindingAction::Char('8');
+        "9",  ~TermMode::APP_KEYPAD; BindingAction::Char('9');
+        ".",  ~TermMode::APP_KEYPAD; BindingAction::Char('.');
+        ",",  ~TermMode::APP_KEYPAD; BindingAction::Char(',');
+        "+",  ~TermMode::APP_KEYPAD; BindingAction::Char('+');
+        "-",  ~TermMode::APP_KEYPAD; BindingAction::Char('-');
+        "*",  ~TermMode::APP_KEYPAD; BindingAction::Char('*');
+        "/",  ~TermMode::APP_KEYPAD; BindingAction::Char('/');
+        "=",  ~TermMode::APP_KEYPAD; BindingAction::Char('=');
+        "\r", ~TermMode::APP_KEYPAD; BindingAction::Char('\x0d');
+        // APP_KEYPAD Including
+        "0",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOp".into());
+        "1",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOq".into());
+        "2",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOr".into());
+        "3",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOs".into());
+        "4",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOt".into());
+        "5",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOu".into());
+        "6",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOv".into());
+        "7",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOw".into());
+        "8",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOx".into());
+        "9",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOy".into());
+        ".",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOn".into());
+        ",",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOl".into());
+        "+",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOk".into());
+        "-",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOm".into());
+        "*",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOj".into());
+        "/",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOo".into());
+        "=",  +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOX".into());
+        "\r", +TermMode::APP_KEYPAD; BindingAction::Esc("\x1bOM".into());
     )
 }
 
@@ -344,5 +430,299 @@ fn mouse_default_bindings() -> Vec<(Binding<InputKind>, BindingAction)> {
     generate_bindings!(
         MouseBinding;
         Left, Modifiers::COMMAND; BindingAction::LinkOpen;
+        Middle, ~TermMode::MOUSE_MODE; BindingAction::PastePrimary;
+        Middle, Modifiers::SHIFT; BindingAction::PastePrimary;
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHIFT_CTRL: Modifiers = Modifiers::SHIFT.union(Modifiers::CTRL);
+
+    fn bytes(action: BindingAction) -> Option<String> {
+        match action {
+            BindingAction::Char(c) => Some(c.to_string()),
+            BindingAction::Esc(seq) => Some(seq),
+            _ => None,
+        }
+    }
+
+    fn key(named: Named, modifiers: Modifiers, mode: TermMode) -> Option<String> {
+        bytes(BindingsLayout::new().get_action(InputKind::KeyCode(named), modifiers, mode))
+    }
+
+    fn keypad(c: &str, mode: TermMode) -> Option<String> {
+        bytes(BindingsLayout::new().get_action(
+            InputKind::Keypad(c.to_string()),
+            Modifiers::empty(),
+            mode,
+        ))
+    }
+
+    #[test]
+    fn unmodified_cursor_keys_follow_cursor_mode() {
+        let cases = [
+            (Named::ArrowUp, "\x1b[A", "\x1bOA"),
+            (Named::ArrowDown, "\x1b[B", "\x1bOB"),
+            (Named::ArrowRight, "\x1b[C", "\x1bOC"),
+            (Named::ArrowLeft, "\x1b[D", "\x1bOD"),
+            (Named::Home, "\x1b[H", "\x1bOH"),
+            (Named::End, "\x1b[F", "\x1bOF"),
+        ];
+        for (named, normal, app_cursor) in cases {
+            let empty = Modifiers::empty();
+            assert_eq!(
+                key(named, empty, TermMode::empty()).as_deref(),
+                Some(normal)
+            );
+            assert_eq!(
+                key(named, empty, TermMode::APP_CURSOR).as_deref(),
+                Some(app_cursor)
+            );
+        }
+    }
+
+    #[test]
+    fn unmodified_named_keys() {
+        let cases = [
+            (Named::Insert, "\x1b[2~"),
+            (Named::Delete, "\x1b[3~"),
+            (Named::PageUp, "\x1b[5~"),
+            (Named::PageDown, "\x1b[6~"),
+            (Named::F1, "\x1bOP"),
+            (Named::F4, "\x1bOS"),
+            (Named::F5, "\x1b[15~"),
+            (Named::F12, "\x1b[24~"),
+            (Named::Enter, "\r"),
+            (Named::Backspace, "\x7f"),
+            (Named::Escape, "\x1b"),
+            (Named::Tab, "\t"),
+        ];
+        for (named, expected) in cases {
+            assert_eq!(
+                key(named, Modifiers::empty(), TermMode::empty()).as_deref(),
+                Some(expected),
+                "{:?}",
+                named
+            );
+        }
+    }
+
+    #[test]
+    fn modified_named_keys_carry_xterm_parameter() {
+        let combos = [
+            (Modifiers::SHIFT, 2),
+            (Modifiers::ALT, 3),
+            (Modifiers::SHIFT | Modifiers::ALT, 4),
+            (Modifiers::CTRL, 5),
+            (SHIFT_CTRL, 6),
+            (Modifiers::CTRL | Modifiers::ALT, 7),
+            (Modifiers::SHIFT | Modifiers::CTRL | Modifiers::ALT, 8),
+        ];
+        let mode = TermMode::ALT_SCREEN;
+        for (named, number, terminator) in MODIFIABLE_KEYS {
+            for (modifiers, parameter) in combos {
+                let is_cursor_key = matches!(
+                    named,
+                    Named::ArrowUp
+                        | Named::ArrowDown
+                        | Named::ArrowLeft
+                        | Named::ArrowRight
+                        | Named::Home
+                        | Named::End
+                );
+                // CTRL + SHIFT cursor keys are reserved and Insert handles primary selection
+                let is_reserved = modifiers == SHIFT_CTRL && is_cursor_key
+                    || named == Named::Insert
+                        && (modifiers == Modifiers::SHIFT || modifiers == SHIFT_CTRL);
+                let expected =
+                    (!is_reserved).then(|| format!("\x1b[{};{}{}", number, parameter, terminator));
+                assert_eq!(
+                    key(named, modifiers, mode),
+                    expected,
+                    "{:?} {:?}",
+                    named,
+                    modifiers
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn specific_modified_keys() {
+        let mode = TermMode::empty();
+        assert_eq!(
+            key(Named::Insert, Modifiers::CTRL, mode).as_deref(),
+            Some("\x1b[2;5~")
+        );
+        assert_eq!(
+            key(Named::F1, Modifiers::CTRL, mode).as_deref(),
+            Some("\x1b[1;5P")
+        );
+        assert_eq!(
+            key(Named::ArrowLeft, Modifiers::CTRL, mode).as_deref(),
+            Some("\x1b[1;5D")
+        );
+        assert_eq!(
+            key(Named::F20, Modifiers::SHIFT, mode).as_deref(),
+            Some("\x1b[34;2~")
+        );
+        assert_eq!(
+            key(Named::Tab, Modifiers::SHIFT, mode).as_deref(),
+            Some("\x1b[Z")
+        );
+        assert_eq!(
+            key(Named::Backspace, Modifiers::ALT, mode).as_deref(),
+            Some("\x1b\x7f")
+        );
+    }
+
+    #[test]
+    fn hotkeys_take_the_place_of_key_encodings() {
+        let mut layout = BindingsLayout::new();
+        layout.add_bindings(crate::hotkeys::bindings(Modifiers::ALT, &["d".to_string()]));
+        let action = |input, modifiers| layout.get_action(input, modifiers, TermMode::empty());
+
+        for modifiers in [
+            Modifiers::ALT,
+            Modifiers::ALT | Modifiers::SHIFT,
+            Modifiers::ALT | Modifiers::CTRL,
+        ] {
+            assert_eq!(
+                action(InputKind::KeyCode(Named::ArrowLeft), modifiers),
+                BindingAction::Hotkey
+            );
+        }
+        assert_eq!(
+            action(InputKind::Char("q".into()), Modifiers::ALT),
+            BindingAction::Hotkey
+        );
+        assert_eq!(
+            action(
+                InputKind::Char("d".into()),
+                Modifiers::ALT | Modifiers::SHIFT
+            ),
+            BindingAction::Hotkey
+        );
+
+        // Keys that aren't hotkeys still reach the program
+        assert_eq!(
+            action(InputKind::Char(".".into()), Modifiers::ALT),
+            BindingAction::Ignore
+        );
+        assert_eq!(
+            action(
+                InputKind::Char("g".into()),
+                Modifiers::ALT | Modifiers::SHIFT
+            ),
+            BindingAction::Ignore
+        );
+        assert_eq!(
+            bytes(action(InputKind::KeyCode(Named::Delete), Modifiers::ALT)).as_deref(),
+            Some("\x1b[3;3~")
+        );
+        assert_eq!(
+            bytes(action(
+                InputKind::KeyCode(Named::ArrowLeft),
+                Modifiers::CTRL
+            ))
+            .as_deref(),
+            Some("\x1b[1;5D")
+        );
+    }
+
+    #[test]
+    fn shift_paging_keys_only_reach_the_alternate_screen() {
+        for named in [Named::PageUp, Named::PageDown, Named::Home, Named::End] {
+            assert_eq!(key(named, Modifiers::SHIFT, TermMode::empty()), None);
+            assert!(key(named, Modifiers::SHIFT, TermMode::ALT_SCREEN).is_some());
+        }
+    }
+
+    #[test]
+    fn ctrl_shift_cursor_keys_are_reserved() {
+        let layout = BindingsLayout::new();
+        for named in [Named::ArrowUp, Named::ArrowDown, Named::Home, Named::End] {
+            assert_eq!(
+                layout.get_action(InputKind::KeyCode(named), SHIFT_CTRL, TermMode::empty()),
+                BindingAction::Ignore
+            );
+        }
+    }
+
+    #[test]
+    fn insert_handles_the_primary_selection() {
+        let layout = BindingsLayout::new();
+        let insert = InputKind::KeyCode(Named::Insert);
+        assert_eq!(
+            layout.get_action(insert.clone(), Modifiers::SHIFT, TermMode::empty()),
+            BindingAction::PastePrimary
+        );
+        assert_eq!(
+            layout.get_action(insert, SHIFT_CTRL, TermMode::empty()),
+            BindingAction::CopyPrimary
+        );
+    }
+
+    #[test]
+    fn ctrl_letters_send_control_characters() {
+        let layout = BindingsLayout::new();
+        for (i, c) in ('a'..='z').enumerate() {
+            assert_eq!(
+                layout.get_action(
+                    InputKind::Char(c.to_string()),
+                    Modifiers::CTRL,
+                    TermMode::empty()
+                ),
+                BindingAction::Char(char::from(i as u8 + 1)),
+                "{}",
+                c
+            );
+        }
+    }
+
+    #[test]
+    fn keypad_follows_keypad_mode() {
+        let cases = [
+            ("0", "\x1bOp"),
+            ("1", "\x1bOq"),
+            ("5", "\x1bOu"),
+            ("9", "\x1bOy"),
+            (".", "\x1bOn"),
+            (",", "\x1bOl"),
+            ("+", "\x1bOk"),
+            ("-", "\x1bOm"),
+            ("*", "\x1bOj"),
+            ("/", "\x1bOo"),
+            ("=", "\x1bOX"),
+            ("\r", "\x1bOM"),
+        ];
+        for (c, app_keypad) in cases {
+            assert_eq!(keypad(c, TermMode::empty()).as_deref(), Some(c));
+            assert_eq!(keypad(c, TermMode::APP_KEYPAD).as_deref(), Some(app_keypad));
+        }
+    }
+
+    #[test]
+    fn middle_click_pastes_the_primary_selection() {
+        let layout = BindingsLayout::new();
+        let middle = || InputKind::Mouse(Button::Middle);
+        assert_eq!(
+            layout.get_action(middle(), Modifiers::empty(), TermMode::empty()),
+            BindingAction::PastePrimary
+        );
+        // Programs reporting the mouse get the middle click instead.
+        for mode in [
+            TermMode::MOUSE_REPORT_CLICK,
+            TermMode::MOUSE_DRAG,
+            TermMode::MOUSE_MOTION,
+        ] {
+            assert_ne!(
+                layout.get_action(middle(), Modifiers::empty(), mode),
+                BindingAction::PastePrimary
+            );
+        }
+    }
+}
@@ -0,0 +1,442 @@
+//! Key encoding for the kitty keyboard protocol, used in place of the
+//! bindings once a program turns on any of its progressive enhancements.
+//!
+//! See <https://sw.kovidgoyal.net/kitty/keyboard-protocol/>.
+
+use alacritty_terminal::term::TermMode;
+use iced_core::keyboard::key::Named;
+use iced_core::keyboard::{Key, Location, Modifiers};
+
+/// How a key is identified in an escape code: the number and the final
+/// character that ends the sequence.
+struct Code {
+    number: u32,
+    terminator: char,
+    /// Typed text rather than a functional key, so Shift alone keeps the
+    /// legacy encoding.
+    is_text: bool,
+}
+
+impl Code {
+    const fn text(number: u32) -> Self {
+        Self {
+            number,
+            terminator: 'u',
+            is_text: true,
+        }
+    }
+
+    const fn functional(number: u32, terminator: char) -> Self {
+        Self {
+            number,
+            terminator,
+            is_text: false,
+        }
+    }
+}
+
+/// The escape code for a key press or release under the enhancements in
+/// `mode`. `None` leaves the key to the legacy encoding, and an empty
+/// sequence means the key sends nothing.
+pub fn encode(
+    key: &Key,
+    location: Location,
+    modifiers: Modifiers,
+    text: Option<&str>,
+    released: bool,
+    mode: TermMode,
+) -> Option<Vec<u8>> {
+    let report_all = mode.contains(TermMode::REPORT_ALL_KEYS_AS_ESC);
+    let disambiguate = report_all || mode.contains(TermMode::DISAMBIGUATE_ESC_CODES);
+    let event_types = mode.contains(TermMode::REPORT_EVENT_TYPES);
+
+    if released && !event_types {
+        return Some(Vec::new());
+    }
+
+    let code = match key.as_ref() {
+        Key::Character(c) => {
+            let c = c.chars().next()?.to_lowercase().next()?;
+            match (location, keypad(c)) {
+                (Location::Numpad, Some(number)) => Code::text(number),
+                _ => Code::text(c as u32),
+            }
+        }
+        Key::Named(named) => functional(named, location)?,
+        Key::Unidentified => return None,
+    };
+
+    let is_modifier = (57441..=57452).contains(&code.number);
+    let text_modifiers = modifiers.control() || modifiers.alt() || modifiers.logo();
+    let escaped = report_all
+        || (disambiguate
+            && match code.number {
+                27 => true,
+                _ if is_modifier => false,
+                9 | 13 | 127 => !modifiers.is_empty(),
+                _ if code.is_text => text_modifiers,
+                _ => !modifiers.is_empty() || event_types,
+            });
+
+    if !escaped {
+        return if released || is_modifier {
+            Some(Vec::new())
+        } else {
+            None
+        };
+    }
+
+    let mut number = code.number.to_string();
+    if code.is_text
+        && mode.contains(TermMode::REPORT_ALTERNATE_KEYS)
+        && modifiers.shift()
+        && let Some(shifted) = text.and_then(|text| text.chars().next())
+        && shifted as u32 != code.number
+        && !shifted.is_control()
+    {
+        number = format!("{}:{}", number, shifted as u32);
+    }
+
+    let mut bits = 0;
+    if modifiers.shift() {
+        bits |= 1;
+    }
+    if modifiers.alt() {
+        bits |= 2;
+    }
+    if modifiers.control() {
+        bits |= 4;
+    }
+    if modifiers.logo() {
+        bits |= 8;
+    }
+
+    let associated_text = text
+        .filter(|_| report_all && !released && mode.contains(TermMode::REPORT_ASSOCIATED_TEXT))
+        .filter(|text| !text.chars().any(char::is_control))
+        .map(|text| {
+            text.chars()
+                .map(|c| (c as u32).to_string())
+                .collect::<Vec<_>>()
+                .join(":")
+        });
+
+    let mut parameters = String::new();
+    if released {
+        parameters = format!(";{}:3", bits + 1);
+    } else if bits != 0 || associated_text.is_some() {
+        parameters = format!(";{}", bits + 1);
+    }
+    if let Some(associated_text) = associated_text {
+        parameters = format!("{};{}", parameters, associated_text);
+    }
+
+    // Keys ending in a letter are numbered 1, which can go unsaid
+    let sequence =
+        if code.terminator.is_ascii_alphabetic() && code.terminator != 'u' && parameters.is_empty()
+        {
+            format!("\x1b[{}", code.terminator)
+        } else {
+            format!("\x1b[{}{}{}", number, parameters, code.terminator)
+        };
+    Some(sequence.into_bytes())
+}
+
+fn keypad(c: char) -> Option<u32> {
+    match c {
+        '0'..='9' => Some(57399 + c as u32 - '0' as u32),
+        '.' => Some(57409),
+        '/' => Some(57410),
+        '*' => Some(57411),
+        '-' => Some(57412),
+        '+' => Some(57413),
+        '=' => Some(57415),
+        _ => None,
+    }
+}
+
+fn functional(named: Named, location: Location) -> Option<Code> {
+    let right = location == Location::Right;
+    let code = match named {
+        Named::Space => Code::text(32),
+        Named::Escape => Code::functional(27, 'u'),
+        Named::Enter if location == Location::Numpad => Code::functional(57414, 'u'),
+        Named::Enter => Code::functional(13, 'u'),
+        Named::Tab => Code::functional(9, 'u'),
+        Named::Backspace => Code::functional(127, 'u'),
+        Named::Insert => Code::functional(2, '~'),
+        Named::Delete => Code::functional(3, '~'),
+        Named::ArrowLeft => Code::functional(1, 'D'),
+        Named::ArrowRight => Code::functional(1, 'C'),
+        Named::ArrowUp => Code::functional(1, 'A'),
+        Named::ArrowDown => Code::functional(1, 'B'),
+        Named::PageUp => Code::functional(5, '~'),
+        Named::PageDown => Code::functional(6, '~'),
+        Named::Home => Code::functional(1, 'H'),
+        Named::End => Code::functional(1, 'F'),
+        Named::CapsLock => Code::functional(57358, 'u'),
+        Named::ScrollLock => Code::functional(57359, 'u'),
+        Named::NumLock => Code::functional(57360, 'u'),
+        Named::PrintScreen => Code::functional(57361, 'u'),
+        Named::Pause => Code::functional(57362, 'u'),
+        Named::ContextMenu => Code::functional(57363, 'u'),
+        Named::F1 => Code::functional(1, 'P'),
+        Named::F2 => Code::functional(1, 'Q'),
+        Named::F3 => Code::functional(13, '~'),
+        Named::F4 => Code::functional(1, 'S'),
+        Named::F5 => Code::functional(15, '~'),
+        Named::F6 => Code::functional(17, '~'),
+        Named::F7 => Code::functional(18, '~'),
+        Named::F8 => Code::functional(19, '~'),
+        Named::F9 => Code::functional(20, '~'),
+        Named::F10 => Code::functional(21, '~'),
+        Named::F11 => Code::functional(23, '~'),
+        Named::F12 => Code::functional(24, '~'),
+        Named::F13 => Code::functional(57376, 'u'),
+        Named::F14 => Code::functional(57377, 'u'),
+        Named::F15 => Code::functional(57378, 'u'),
+        Named::F16 => Code::functional(57379, 'u'),
+        Named::F17 => Code::functional(57380, 'u'),
+        Named::F18 => Code::functional(57381, 'u'),
+        Named::F19 => Code::functional(57382, 'u'),
+        Named::F20 => Code::functional(57383, 'u'),
+        Named::Shift => Code::functional(if right { 57447 } else { 57441 }, 'u'),
+        Named::Control => Code::functional(if right { 57448 } else { 57442 }, 'u'),
+        Named::Alt => Code::functional(if right { 57449 } else { 57443 }, 'u'),
+        Named::Super => Code::functional(if right { 57450 } else { 57444 }, 'u'),
+        Named::Hyper => Code::functional(if right { 57451 } else { 57445 }, 'u'),
+        Named::Meta => Code::functional(if right { 57452 } else { 57446 }, 'u'),
+        _ => return None,
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISAMBIGUATE: TermMode = TermMode::DISAMBIGUATE_ESC_CODES;
+    const REPORT_ALL: TermMode = TermMode::REPORT_ALL_KEYS_AS_ESC;
+
+    fn char(c: &str) -> Key {
+        Key::Character(c.into())
+    }
+
+    fn press(key: Key, modifiers: Modifiers, text: Option<&str>, mode: TermMode) -> Option<String> {
+        encode(&key, Location::Standard, modifiers, text, false, mode)
+            .map(|bytes| String::from_utf8(bytes).unwrap())
+    }
+
+    fn release(key: Key, modifiers: Modifiers, mode: TermMode) -> Option<String> {
+        encode(&key, Location::Standard, modifiers, None, true, mode)
+            .map(|bytes| String::from_utf8(bytes).unwrap())
+    }
+
+    #[test]
+    fn disambiguate_escapes_ambiguous_keys() {
+        let empty = Modifiers::empty();
+        assert_eq!(press(char("a"), empty, Some("a"), DISAMBIGUATE), None);
+        assert_eq!(
+            press(char("a"), Modifiers::SHIFT, Some("A"), DISAMBIGUATE),
+            None
+        );
+        assert_eq!(
+            press(char("a"), Modifiers::CTRL, None, DISAMBIGUATE).as_deref(),
+            Some("\x1b[97;5u")
+        );
+        assert_eq!(
+            press(char("i"), Modifiers::CTRL, None, DISAMBIGUATE).as_deref(),
+            Some("\x1b[105;5u")
+        );
+        assert_eq!(
+            press(char("a"), Modifiers::ALT, Some("a"), DISAMBIGUATE).as_deref(),
+            Some("\x1b[97;3u")
+        );
+        assert_eq!(
+            press(Key::Named(Named::Escape), empty, None, DISAMBIGUATE).as_deref(),
+            Some("\x1b[27u")
+        );
+        assert_eq!(
+            press(Key::Named(Named::Enter), empty, Some("\r"), DISAMBIGUATE),
+            None
+        );
+        assert_eq!(
+            press(Key::Named(Named::Tab), Modifiers::SHIFT, None, DISAMBIGUATE).as_deref(),
+            Some("\x1b[9;2u")
+        );
+    }
+
+    #[test]
+    fn disambiguate_keeps_legacy_functional_keys() {
+        let empty = Modifiers::empty();
+        assert_eq!(
+            press(Key::Named(Named::ArrowUp), empty, None, DISAMBIGUATE),
+            None
+        );
+        assert_eq!(
+            press(Key::Named(Named::F1), empty, None, DISAMBIGUATE),
+            None
+        );
+        assert_eq!(
+            press(Key::Named(Named::F1), Modifiers::CTRL, None, DISAMBIGUATE).as_deref(),
+            Some("\x1b[1;5P")
+        );
+        assert_eq!(
+            press(Key::Named(Named::F3), Modifiers::SHIFT, None, DISAMBIGUATE).as_deref(),
+            Some("\x1b[13;2~")
+        );
+        assert_eq!(
+            press(
+                Key::Named(Named::Delete),
+                Modifiers::ALT,
+                None,
+                DISAMBIGUATE
+            )
+            .as_deref(),
+            Some("\x1b[3;3~")
+        );
+        assert_eq!(
+            press(Key::Named(Named::F13), Modifiers::CTRL, None, DISAMBIGUATE).as_deref(),
+            Some("\x1b[57376;5u")
+        );
+    }
+
+    #[test]
+    fn releases_need_event_types() {
+        assert_eq!(
+            release(char("a"), Modifiers::CTRL, DISAMBIGUATE).as_deref(),
+            Some("")
+        );
+
+        let mode = DISAMBIGUATE | TermMode::REPORT_EVENT_TYPES;
+        assert_eq!(
+            press(char("a"), Modifiers::CTRL, None, mode).as_deref(),
+            Some("\x1b[97;5u")
+        );
+        assert_eq!(
+            release(char("a"), Modifiers::CTRL, mode).as_deref(),
+            Some("\x1b[97;5:3u")
+        );
+        assert_eq!(
+            release(Key::Named(Named::Escape), Modifiers::empty(), mode).as_deref(),
+            Some("\x1b[27;1:3u")
+        );
+        assert_eq!(
+            press(Key::Named(Named::ArrowUp), Modifiers::empty(), None, mode).as_deref(),
+            Some("\x1b[A")
+        );
+        assert_eq!(
+            release(Key::Named(Named::ArrowUp), Modifiers::empty(), mode).as_deref(),
+            Some("\x1b[1;1:3A")
+        );
+        // Releasing a key that typed text isn't reported without all keys
+        assert_eq!(
+            release(char("a"), Modifiers::empty(), mode).as_deref(),
+            Some("")
+        );
+    }
+
+    #[test]
+    fn alternate_keys_add_the_shifted_key() {
+        let mode = DISAMBIGUATE | TermMode::REPORT_ALTERNATE_KEYS;
+        assert_eq!(
+            press(
+                char("a"),
+                Modifiers::CTRL | Modifiers::SHIFT,
+                Some("A"),
+                mode
+            )
+            .as_deref(),
+            Some("\x1b[97:65;6u")
+        );
+        assert_eq!(
+            press(char("a"), Modifiers::CTRL, Some("a"), mode).as_deref(),
+            Some("\x1b[97;5u")
+        );
+        // Control characters aren't alternate keys
+        assert_eq!(
+            press(
+                char("a"),
+                Modifiers::CTRL | Modifiers::SHIFT,
+                Some("\x01"),
+                mode
+            )
+            .as_deref(),
+            Some("\x1b[97;6u")
+        );
+    }
+
+    #[test]
+    fn report_all_keys_escapes_everything() {
+        let empty = Modifiers::empty();
+        assert_eq!(
+            press(char("a"), empty, Some("a"), REPORT_ALL).as_deref(),
+            Some("\x1b[97u")
+        );
+        assert_eq!(
+            press(char("a"), Modifiers::SHIFT, Some("A"), REPORT_ALL).as_deref(),
+            Some("\x1b[97;2u")
+        );
+        assert_eq!(
+            press(Key::Named(Named::Enter), empty, Some("\r"), REPORT_ALL).as_deref(),
+            Some("\x1b[13u")
+        );
+        assert_eq!(
+            press(Key::Named(Named::Backspace), empty, None, REPORT_ALL).as_deref(),
+            Some("\x1b[127u")
+        );
+        assert_eq!(
+            press(Key::Named(Named::Shift), Modifiers::SHIFT, None, REPORT_ALL).as_deref(),
+            Some("\x1b[57441;2u")
+        );
+        assert_eq!(
+            encode(
+                &Key::Named(Named::Control),
+                Location::Right,
+                Modifiers::CTRL,
+                None,
+                false,
+                REPORT_ALL
+            ),
+            Some(b"\x1b[57448;5u".to_vec())
+        );
+        assert_eq!(
+            encode(
+                &char("1"),
+                Location::Numpad,
+                empty,
+                Some("1"),
+                false,
+                REPORT_ALL
+            ),
+            Some(b"\x1b[57400u".to_vec())
+        );
+    }
+
+    #[test]
+    fn associated_text_follows_the_key() {
+        let mode = REPORT_ALL | TermMode::REPORT_ASSOCIATED_TEXT;
+        assert_eq!(
+            press(char("a"), Modifiers::empty(), Some("a"), mode).as_deref(),
+            Some("\x1b[97;1;97u")
+        );
+        assert_eq!(
+            press(char("a"), Modifiers::SHIFT, Some("A"), mode).as_deref(),
+            Some("\x1b[97;2;65u")
+        );
+        // Only printable text is sent along
+        assert_eq!(
+            press(char("a"), Modifiers::CTRL, Some("\x01"), mode).as_deref(),
+            Some("\x1b[97;5u")
+        );
+        assert_eq!(
+            release(
+                char("a"),
+                Modifiers::empty(),
+                mode | TermMode::REPORT_EVENT_TYPES
+            )
+            .as_deref(),
+            Some("\x1b[97;1:3u")
+        );
+    }
+}
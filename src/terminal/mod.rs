@@ -4,14 +4,19 @@ pub mod settings;
 
 mod backend;
 mod font;
+mod kitty;
 mod subscription;
+#[allow(clippy::module_inception)]
 mod terminal;
 mod theme;
 mod view;
 
 pub use alacritty_terminal::event::Event as AlacrittyEvent;
-pub use alacritty_terminal::term::TermMode;
+pub use backend::BackendCommand;
+pub use backend::modes::{MouseEncodings, Scanner};
+pub use backend::process::Process;
+pub use backend::term_config;
 pub use subscription::Subscription;
 pub use terminal::{Command, Event, Terminal};
-pub use theme::{ColorPalette, Theme};
+pub use theme::ColorPalette;
 pub use view::TerminalView;
@@ -1,5 +1,10 @@
+use crate::config;
 use crate::terminal::ColorPalette;
+use crate::terminal::bindings::{Binding, BindingAction, InputKind, parse_modifiers};
 use iced::Font;
+use iced::keyboard::Modifiers;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 #[cfg(target_os = "windows")]
 const DEFAULT_SHELL: &str = "wsl.exe";
@@ -12,12 +17,23 @@ pub struct Settings {
     pub font: FontSettings,
     pub theme: ThemeSettings,
     pub backend: BackendSettings,
+    pub keyboard: KeyboardSettings,
 }
 
 #[derive(Debug, Clone)]
 pub struct BackendSettings {
     pub program: String,
     pub args: Vec<String>,
+    pub working_directory: Option<PathBuf>,
+    pub env: HashMap<String, String>,
+    pub session: Option<Session>,
+}
+
+/// Where the shell of a terminal runs when frostty is attached to a daemon.
+#[derive(Debug, Clone, Copy)]
+pub enum Session {
+    Create { workspace: u32 },
+    Attach(u64),
 }
 
 impl Default for BackendSettings {
@@ -25,6 +41,57 @@ impl Default for BackendSettings {
         Self {
             program: DEFAULT_SHELL.to_string(),
             args: vec![],
+            working_directory: None,
+            env: HashMap::new(),
+            session: None,
+        }
+    }
+}
+
+/// Super rather than Alt, so Alt stays Meta for readline and emacs bindings.
+pub const DEFAULT_APP_MODIFIER: Modifiers = Modifiers::LOGO;
+
+#[derive(Debug, Clone)]
+pub struct KeyboardSettings {
+    /// The modifiers that make a key press a frostty hotkey instead of input.
+    pub app_modifier: Modifiers,
+    /// Bindings for the hotkeys, which never reach the terminal.
+    pub hotkeys: Vec<(Binding<InputKind>, BindingAction)>,
+    /// Meta sets the eighth bit instead of sending an escape first.
+    pub meta_8bit: bool,
+    /// Whether the left and right Alt keys act as Meta.
+    pub option_as_alt: [bool; 2],
+}
+
+impl KeyboardSettings {
+    pub fn new(keyboard: Option<config::Keyboard>) -> Self {
+        let Some(keyboard) = keyboard else {
+            return Self::default();
+        };
+        Self {
+            app_modifier: keyboard
+                .app_modifier
+                .map(|text| parse_modifiers(&text).expect("improperly formatted app modifier"))
+                .unwrap_or(DEFAULT_APP_MODIFIER),
+            hotkeys: vec![],
+            meta_8bit: keyboard.meta.is_some_and(|meta| meta == "8bit"),
+            option_as_alt: match keyboard.option_as_alt.as_deref() {
+                Some("none") => [false, false],
+                Some("left") => [true, false],
+                Some("right") => [false, true],
+                _ => [true, true],
+            },
+        }
+    }
+}
+
+impl Default for KeyboardSettings {
+    fn default() -> Self {
+        Self {
+            app_modifier: DEFAULT_APP_MODIFIER,
+            hotkeys: vec![],
+            meta_8bit: false,
+            option_as_alt: [true, true],
         }
     }
 }
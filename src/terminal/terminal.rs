@@ -1,19 +1,26 @@
 use crate::terminal::AlacrittyEvent;
+use crate::terminal::Process;
 use crate::terminal::actions::Action;
 use crate::terminal::backend::{Backend, BackendCommand};
 use crate::terminal::bindings::{Binding, BindingAction, BindingsLayout, InputKind};
 use crate::terminal::font::TermFont;
-use crate::terminal::settings::{BackendSettings, FontSettings, Settings, ThemeSettings};
+use crate::terminal::settings::{
+    BackendSettings, FontSettings, KeyboardSettings, Settings, ThemeSettings,
+};
 use crate::terminal::theme::{ColorPalette, Theme};
+use alacritty_terminal::term::TermMode;
 use iced::widget::canvas::Cache;
+use std::time::Instant;
 use tokio::sync::mpsc::Sender;
 
 #[derive(Debug, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum Event {
     CommandReceived(u64, Command),
 }
 
 #[derive(Debug, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum Command {
     InitBackend(Sender<AlacrittyEvent>),
     ChangeTheme(Box<ColorPalette>),
@@ -28,20 +35,33 @@ pub struct Terminal {
     pub(crate) theme: Theme,
     pub(crate) cache: Cache,
     pub(crate) bindings: BindingsLayout,
+    pub(crate) keyboard: KeyboardSettings,
     pub(crate) backend: Option<Backend>,
+    /// Title last set by the program running in the terminal.
+    pub title: Option<String>,
+    /// The program in the foreground, or the shell, as of the last refresh.
+    pub process: Option<Process>,
+    /// When the program last wrote to the terminal.
+    pub last_output: Instant,
     backend_settings: BackendSettings,
 }
 
 impl Terminal {
     pub fn new(id: u64, settings: Settings) -> Self {
+        let mut bindings = BindingsLayout::default();
+        bindings.add_bindings(settings.keyboard.hotkeys.clone());
         Self {
             id,
             font: TermFont::new(settings.font),
             theme: Theme::new(settings.theme),
-            bindings: BindingsLayout::default(),
+            bindings,
+            keyboard: settings.keyboard,
             cache: Cache::default(),
             backend_settings: settings.backend,
             backend: None,
+            title: None,
+            process: None,
+            last_output: Instant::now(),
         }
     }
 
@@ -84,6 +104,9 @@ impl Terminal {
                 self.bindings.add_bindings(bindings);
             }
             Command::ProcessBackendCommand(c) => {
+                if let BackendCommand::ProcessAlacrittyEvent(AlacrittyEvent::Wakeup) = c {
+                    self.last_output = Instant::now();
+                }
                 if let Some(ref mut backend) = self.backend {
                     action = backend.process_command(c);
                     if action == Action::Redraw {
@@ -93,9 +116,68 @@ impl Terminal {
             }
         }
 
+        if let Action::ChangeTitle(ref title) = action {
+            self.title = Some(title.clone());
+        }
         action
     }
 
+    pub fn kill(&self) {
+        if let Some(ref backend) = self.backend {
+            backend.kill();
+        }
+    }
+
+    /// The program in the foreground of the terminal, if it isn't the shell.
+    pub fn foreground_process(&self) -> Option<Process> {
+        self.backend
+            .as_ref()
+            .and_then(|backend| backend.foreground_process())
+    }
+
+    /// Looks up the program in the foreground and its working directory again.
+    pub fn refresh_process(&mut self) {
+        self.process = self
+            .backend
+            .as_ref()
+            .and_then(|backend| backend.current_process());
+    }
+
+    /// Whether pasting `text` could run commands by itself, because the
+    /// program didn't ask for bracketed paste and the text holds line breaks
+    /// or other control characters.
+    pub fn is_unsafe_paste(&self, text: &str) -> bool {
+        let bracketed = self.backend.as_ref().is_some_and(|backend| {
+            backend
+                .renderable_content()
+                .terminal_mode
+                .contains(TermMode::BRACKETED_PASTE)
+        });
+        could_run_commands(text, bracketed)
+    }
+
+    /// Tells the program about gaining or losing focus, if it asked to
+    /// know with focus reporting.
+    pub fn report_focus(&mut self, focused: bool) {
+        if let Some(ref mut backend) = self.backend
+            && backend
+                .renderable_content()
+                .terminal_mode
+                .contains(TermMode::FOCUS_IN_OUT)
+        {
+            let seq = if focused { "\x1b[I" } else { "\x1b[O" };
+            backend.process_command(BackendCommand::Write(seq.as_bytes().to_vec()));
+        }
+    }
+
+    /// Whether the shell keeps running in the daemon once the terminal is
+    /// dropped.
+    pub fn is_remote(&self) -> bool {
+        self.backend
+            .as_ref()
+            .is_some_and(|backend| backend.is_remote())
+    }
+
     fn sync_and_redraw(&mut self) {
         if let Some(ref mut backend) = self.backend {
             backend.sync();
@@ -110,3 +192,28 @@ impl Terminal {
         self.cache.clear();
     }
 }
+
+/// Whether pasting `text` could run commands by itself, see
+/// [`Terminal::is_unsafe_paste`].
+fn could_run_commands(text: &str, bracketed: bool) -> bool {
+    !bracketed && text.chars().any(|c| c.is_control() && c != '\t')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pastes_with_line_breaks_are_unsafe_unless_bracketed() {
+        assert!(could_run_commands("make\n", false));
+        assert!(could_run_commands("echo\rrm", false));
+        assert!(could_run_commands("\x1b[A", false));
+        assert!(!could_run_commands("make\n", true));
+    }
+
+    #[test]
+    fn plain_text_and_tabs_are_safe() {
+        assert!(!could_run_commands("git commit -m 'tidy up'", false));
+        assert!(!could_run_commands("a\tb", false));
+    }
+}
@@ -1,7 +1,6 @@
-use crate::terminal::backend::{
-    Backend, BackendCommand, LinkAction, MouseButton, RenderableContent,
-};
+use crate::terminal::backend::{Backend, BackendCommand, LinkAction, MouseButton};
 use crate::terminal::bindings::{BindingAction, BindingsLayout, InputKind};
+use crate::terminal::kitty;
 use crate::terminal::theme::TerminalStyle;
 use crate::terminal::{Command, Event, Terminal};
 use alacritty_terminal::index::Point as TerminalGridPoint;
@@ -14,13 +13,17 @@ use iced::widget::canvas::{Path, Text};
 use iced::widget::container;
 use iced::{Element, Length, Point, Rectangle, Size, Theme};
 use iced_core::clipboard::Kind as ClipboardKind;
-use iced_core::keyboard::{Key, Modifiers};
+use iced_core::keyboard::key::Named;
+use iced_core::keyboard::{Key, Location, Modifiers};
 use iced_core::mouse::{self, Click};
 use iced_core::text::{LineHeight, Shaping};
 use iced_core::widget::operation;
 use iced_graphics::core::Widget;
 use iced_graphics::core::widget::{Tree, tree};
 use iced_graphics::geometry::Stroke;
+use std::time::{Duration, Instant};
+
+const AUTO_SCROLL_INTERVAL: Duration = Duration::from_millis(50);
 
 pub struct TerminalView<'a> {
     term: &'a Terminal,
@@ -65,23 +68,41 @@ impl<'a> TerminalView<'a> {
         false
     }
 
+    /// Whether mouse input goes to the program, which holding shift bypasses.
+    fn is_reporting(state: &TerminalViewState, terminal_mode: &TermMode) -> bool {
+        terminal_mode.intersects(TermMode::MOUSE_MODE) && !state.keyboard_modifiers.shift()
+    }
+
+    fn report_button(button: mouse::Button) -> Option<MouseButton> {
+        match button {
+            mouse::Button::Left => Some(MouseButton::LeftButton),
+            mouse::Button::Middle => Some(MouseButton::MiddleButton),
+            mouse::Button::Right => Some(MouseButton::RightButton),
+            _ => None,
+        }
+    }
+
     fn handle_mouse_event(
         &self,
         state: &mut TerminalViewState,
         layout_position: Point,
         cursor_position: Point,
+        clipboard: &mut dyn iced_graphics::core::Clipboard,
         event: iced::mouse::Event,
     ) -> Vec<Command> {
         let mut commands = Vec::new();
         if let Some(backend) = &self.term.backend {
             let terminal_content = backend.renderable_content();
             let terminal_mode = terminal_content.terminal_mode;
+            let is_reporting = Self::is_reporting(state, &terminal_mode);
 
             match event {
                 iced_core::mouse::Event::ButtonPressed(iced_core::mouse::Button::Left) => {
                     Self::handle_left_button_pressed(
                         state,
                         &terminal_mode,
+                        is_reporting,
+                        terminal_content.selectable_range.is_some(),
                         cursor_position,
                         layout_position,
                         &mut commands,
@@ -90,23 +111,90 @@ impl<'a> TerminalView<'a> {
                 iced_core::mouse::Event::CursorMoved { position } => {
                     Self::handle_cursor_moved(
                         state,
-                        backend.renderable_content(),
+                        backend,
+                        is_reporting,
                         position,
                         layout_position,
                         &mut commands,
                     );
                 }
-                iced_core::mouse::Event::ButtonReleased(iced_core::mouse::Button::Left) => {
-                    Self::handle_button_released(
-                        state,
-                        &terminal_mode,
-                        &self.term.bindings,
-                        &mut commands,
-                    );
+                iced_core::mouse::Event::ButtonPressed(iced_core::mouse::Button::Middle)
+                    if self.term.bindings.get_action(
+                        InputKind::Mouse(iced_core::mouse::Button::Middle),
+                        state.keyboard_modifiers,
+                        terminal_mode,
+                    ) == BindingAction::PastePrimary =>
+                {
+                    if let Some(data) = clipboard.read(ClipboardKind::Primary) {
+                        commands.push(Command::ProcessBackendCommand(BackendCommand::Paste(data)));
+                    }
+                }
+                // Right-click extends the selection like shift-click
+                iced_core::mouse::Event::ButtonPressed(iced_core::mouse::Button::Right)
+                    if !is_reporting && terminal_content.selectable_range.is_some() =>
+                {
+                    commands.push(Command::ProcessBackendCommand(
+                        BackendCommand::SelectUpdate((
+                            cursor_position.x - layout_position.x,
+                            cursor_position.y - layout_position.y,
+                        )),
+                    ));
+                    state.is_dragged = true;
+                }
+                iced_core::mouse::Event::ButtonPressed(button) if is_reporting => {
+                    if let Some(report) = Self::report_button(button) {
+                        commands.push(Command::ProcessBackendCommand(BackendCommand::MouseReport(
+                            report,
+                            state.keyboard_modifiers,
+                            state.mouse_position_on_grid,
+                            state.mouse_position,
+                            true,
+                        )));
+                        state.reported_button = Some(button);
+                        state.is_dragged = true;
+                    }
+                }
+                iced_core::mouse::Event::ButtonReleased(button) => {
+                    if is_reporting && let Some(report) = Self::report_button(button) {
+                        commands.push(Command::ProcessBackendCommand(BackendCommand::MouseReport(
+                            report,
+                            state.keyboard_modifiers,
+                            state.mouse_position_on_grid,
+                            state.mouse_position,
+                            false,
+                        )));
+                    }
+
+                    if button == iced_core::mouse::Button::Left {
+                        Self::handle_button_released(
+                            state,
+                            &terminal_mode,
+                            &self.term.bindings,
+                            &mut commands,
+                        );
+                    } else {
+                        state.is_dragged = false;
+                        state.auto_scroll = None;
+                    }
+                    state.reported_button = None;
+
+                    // Finishing a selection makes it the primary selection
+                    if !is_reporting
+                        && matches!(
+                            button,
+                            iced_core::mouse::Button::Left | iced_core::mouse::Button::Right
+                        )
+                    {
+                        let selection = backend.selectable_content();
+                        if !selection.is_empty() {
+                            clipboard.write(ClipboardKind::Primary, selection);
+                        }
+                    }
                 }
                 iced::mouse::Event::WheelScrolled { delta } => {
                     Self::handle_wheel_scrolled(
                         state,
+                        is_reporting,
                         delta,
                         &self.term.font.measure,
                         &mut commands,
@@ -122,24 +210,32 @@ impl<'a> TerminalView<'a> {
     fn handle_left_button_pressed(
         state: &mut TerminalViewState,
         terminal_mode: &TermMode,
+        is_reporting: bool,
+        has_selection: bool,
         cursor_position: Point,
         layout_position: Point,
         commands: &mut Vec<Command>,
     ) {
-        let cmd = if terminal_mode.intersects(TermMode::MOUSE_MODE) {
+        let cmd = if is_reporting {
+            state.reported_button = Some(mouse::Button::Left);
             Command::ProcessBackendCommand(BackendCommand::MouseReport(
                 MouseButton::LeftButton,
                 state.keyboard_modifiers,
                 state.mouse_position_on_grid,
+                state.mouse_position,
                 true,
             ))
+        } else if has_selection
+            && state.keyboard_modifiers == Modifiers::SHIFT
+            && !terminal_mode.intersects(TermMode::MOUSE_MODE)
+        {
+            Command::ProcessBackendCommand(BackendCommand::SelectUpdate((
+                cursor_position.x - layout_position.x,
+                cursor_position.y - layout_position.y,
+            )))
         } else {
             let current_click = Click::new(cursor_position, mouse::Button::Left, state.last_click);
-            let selection_type = match current_click.kind() {
-                mouse::click::Kind::Single => SelectionType::Simple,
-                mouse::click::Kind::Double => SelectionType::Semantic,
-                mouse::click::Kind::Triple => SelectionType::Lines,
-            };
+            let selection_type = selection_type(current_click.kind(), state.keyboard_modifiers);
             state.last_click = Some(current_click);
             Command::ProcessBackendCommand(BackendCommand::SelectStart(
                 selection_type,
@@ -155,13 +251,17 @@ impl<'a> TerminalView<'a> {
 
     fn handle_cursor_moved(
         state: &mut TerminalViewState,
-        terminal_content: &RenderableContent,
+        backend: &Backend,
+        is_reporting: bool,
         position: Point,
         layout_position: Point,
         commands: &mut Vec<Command>,
     ) {
         let cursor_x = position.x - layout_position.x;
         let cursor_y = position.y - layout_position.y;
+        let terminal_content = backend.renderable_content();
+        let last_position = state.mouse_position_on_grid;
+        state.mouse_position = (cursor_x, cursor_y);
         state.mouse_position_on_grid = Backend::selection_point(
             cursor_x,
             cursor_y,
@@ -170,19 +270,37 @@ impl<'a> TerminalView<'a> {
         );
 
         // Handle command or selection update based on terminal mode and modifiers
-        if state.is_dragged {
+        if is_reporting {
+            // Button-event tracking only reports drags, any-event tracking
+            // reports motion with no button held as well
             let terminal_mode = terminal_content.terminal_mode;
-            let cmd = if terminal_mode.intersects(TermMode::MOUSE_MOTION) {
-                Command::ProcessBackendCommand(BackendCommand::MouseReport(
-                    MouseButton::LeftMove,
+            let report = match state.reported_button {
+                Some(mouse::Button::Left) => Some(MouseButton::LeftMove),
+                Some(mouse::Button::Middle) => Some(MouseButton::MiddleMove),
+                Some(mouse::Button::Right) => Some(MouseButton::RightMove),
+                _ if terminal_mode.contains(TermMode::MOUSE_MOTION) => Some(MouseButton::NoneMove),
+                _ => None,
+            }
+            .filter(|_| terminal_mode.intersects(TermMode::MOUSE_DRAG | TermMode::MOUSE_MOTION));
+
+            if let Some(report) = report
+                && (state.mouse_position_on_grid != last_position || backend.reports_pixels())
+            {
+                commands.push(Command::ProcessBackendCommand(BackendCommand::MouseReport(
+                    report,
                     state.keyboard_modifiers,
                     state.mouse_position_on_grid,
+                    state.mouse_position,
                     true,
-                ))
-            } else {
-                Command::ProcessBackendCommand(BackendCommand::SelectUpdate((cursor_x, cursor_y)))
-            };
-            commands.push(cmd);
+                )));
+            }
+        } else if state.is_dragged {
+            // Keep scrolling while the cursor rests past the top or bottom edge
+            state.auto_scroll = (cursor_y < 0.0 || cursor_y >= state.size.height)
+                .then(|| ((cursor_x, cursor_y), Instant::now() + AUTO_SCROLL_INTERVAL));
+            commands.push(Command::ProcessBackendCommand(
+                BackendCommand::SelectUpdate((cursor_x, cursor_y)),
+            ));
         }
 
         // Handle link hover if applicable
@@ -201,15 +319,7 @@ impl<'a> TerminalView<'a> {
         commands: &mut Vec<Command>,
     ) {
         state.is_dragged = false;
-
-        if terminal_mode.intersects(TermMode::MOUSE_MODE) {
-            commands.push(Command::ProcessBackendCommand(BackendCommand::MouseReport(
-                MouseButton::LeftButton,
-                state.keyboard_modifiers,
-                state.mouse_position_on_grid,
-                false,
-            )));
-        }
+        state.auto_scroll = None;
 
         if bindings.get_action(
             InputKind::Mouse(iced_core::mouse::Button::Left),
@@ -226,31 +336,157 @@ impl<'a> TerminalView<'a> {
 
     fn handle_wheel_scrolled(
         state: &mut TerminalViewState,
+        is_reporting: bool,
         delta: ScrollDelta,
         font_measure: &Size<f32>,
         commands: &mut Vec<Command>,
     ) {
-        match delta {
-            ScrollDelta::Lines { y, .. } => {
-                let lines = y.signum() * y.abs().round();
-                commands.push(Command::ProcessBackendCommand(BackendCommand::Scroll(
-                    lines as i32,
-                )));
-            }
+        let lines = match delta {
+            ScrollDelta::Lines { y, .. } => (y.signum() * y.abs().round()) as i32,
             ScrollDelta::Pixels { y, .. } => {
                 state.scroll_pixels -= y;
                 let line_height = font_measure.height; // Assume this method exists and gives the height of a line
                 let lines = (state.scroll_pixels / line_height).trunc();
                 state.scroll_pixels %= line_height;
-                if lines != 0.0 {
-                    commands.push(Command::ProcessBackendCommand(BackendCommand::Scroll(
-                        lines as i32,
-                    )));
-                }
+                lines as i32
             }
+        };
+
+        if lines == 0 {
+            return;
+        }
+
+        if is_reporting {
+            let button = if lines > 0 {
+                MouseButton::ScrollUp
+            } else {
+                MouseButton::ScrollDown
+            };
+            for _ in 0..lines.abs() {
+                commands.push(Command::ProcessBackendCommand(BackendCommand::MouseReport(
+                    button.clone(),
+                    state.keyboard_modifiers,
+                    state.mouse_position_on_grid,
+                    state.mouse_position,
+                    true,
+                )));
+            }
+        } else {
+            commands.push(Command::ProcessBackendCommand(BackendCommand::Scroll(
+                lines,
+            )));
         }
     }
 
+    /// Whether `key` held with `modifiers` is one of frostty's hotkeys.
+    fn is_hotkey(&self, key: &Key, modifiers: Modifiers) -> bool {
+        let input = match key {
+            Key::Character(c) => InputKind::Char(c.to_lowercase()),
+            Key::Named(named) => InputKind::KeyCode(*named),
+            Key::Unidentified => return false,
+        };
+        self.term
+            .bindings
+            .get_action(input, modifiers, TermMode::empty())
+            == BindingAction::Hotkey
+    }
+
+    /// The input for `c` typed with Alt held, as an escape prefix or with
+    /// the eighth bit set, according to `keyboard.meta`.
+    fn meta_input(&self, c: &str, modifiers: Modifiers, terminal_mode: TermMode) -> Vec<u8> {
+        let input = match self.term.bindings.get_action(
+            InputKind::Char(c.to_ascii_lowercase()),
+            modifiers,
+            terminal_mode,
+        ) {
+            BindingAction::Char(c) => c.to_string(),
+            BindingAction::Esc(seq) => seq,
+            _ => c.to_string(),
+        };
+
+        match input.as_bytes() {
+            [byte] if self.term.keyboard.meta_8bit && byte.is_ascii() => vec![byte | 0x80],
+            bytes => [b"\x1b", bytes].concat(),
+        }
+    }
+
+    /// The kitty keyboard protocol encoding of a key event, leaving
+    /// frostty's hotkeys and clipboard bindings to the usual handling.
+    fn kitty_input(
+        &self,
+        event: &iced::keyboard::Event,
+        terminal_mode: TermMode,
+    ) -> Option<Vec<u8>> {
+        let (key, location, modifiers, text, released) = match event {
+            iced::keyboard::Event::KeyPressed {
+                key,
+                location,
+                modifiers,
+                text,
+                ..
+            } => (key, *location, *modifiers, text.as_deref(), false),
+            iced::keyboard::Event::KeyReleased {
+                key,
+                location,
+                modifiers,
+            } => (key, *location, *modifiers, None, true),
+            _ => return None,
+        };
+
+        if self.is_hotkey(key, modifiers) {
+            return None;
+        }
+        let input = match key {
+            Key::Character(c) => InputKind::Char(text.unwrap_or(c).to_ascii_lowercase()),
+            Key::Named(code) => InputKind::KeyCode(*code),
+            Key::Unidentified => return None,
+        };
+        if !released
+            && matches!(
+                self.term
+                    .bindings
+                    .get_action(input, modifiers, terminal_mode),
+                BindingAction::Copy
+                    | BindingAction::Paste
+                    | BindingAction::CopyPrimary
+                    | BindingAction::PastePrimary
+            )
+        {
+            return None;
+        }
+
+        kitty::encode(key, location, modifiers, text, released, terminal_mode)
+    }
+
+    /// The binding of a key pressed on the numeric keypad, which sends
+    /// escape codes of its own in application keypad mode.
+    fn keypad_action(
+        &self,
+        event: &iced::keyboard::Event,
+        terminal_mode: TermMode,
+    ) -> Option<BindingAction> {
+        let iced::keyboard::Event::KeyPressed {
+            key,
+            location: Location::Numpad,
+            modifiers,
+            ..
+        } = event
+        else {
+            return None;
+        };
+
+        let input = match key.as_ref() {
+            Key::Character(c) => InputKind::Keypad(c.to_string()),
+            Key::Named(Named::Enter) => InputKind::Keypad("\r".to_string()),
+            _ => return None,
+        };
+        let action = self
+            .term
+            .bindings
+            .get_action(input, *modifiers, terminal_mode);
+        (action != BindingAction::Ignore).then_some(action)
+    }
+
     fn handle_keyboard_event(
         &self,
         state: &mut TerminalViewState,
@@ -260,50 +496,109 @@ impl<'a> TerminalView<'a> {
         if let Some(backend) = &self.term.backend {
             let mut binding_action = BindingAction::Ignore;
             let last_content = backend.renderable_content();
-            match event {
-                iced::keyboard::Event::ModifiersChanged(m) => {
-                    state.keyboard_modifiers = m;
-                    let action = if state.keyboard_modifiers == Modifiers::COMMAND {
-                        LinkAction::Hover
-                    } else {
-                        LinkAction::Clear
-                    };
-                    return Some(Command::ProcessBackendCommand(BackendCommand::ProcessLink(
-                        action,
-                        state.mouse_position_on_grid,
-                    )));
+            if let iced::keyboard::Event::KeyPressed {
+                key: Key::Named(Named::Alt),
+                location,
+                ..
+            }
+            | iced::keyboard::Event::KeyReleased {
+                key: Key::Named(Named::Alt),
+                location,
+                ..
+            } = event
+            {
+                let held = matches!(event, iced::keyboard::Event::KeyPressed { .. });
+                match location {
+                    Location::Left => state.alt_keys[0] = held,
+                    Location::Right => state.alt_keys[1] = held,
+                    _ => {}
                 }
-                iced::keyboard::Event::KeyPressed {
-                    key,
-                    modifiers,
-                    text,
-                    ..
-                } => match key {
-                    Key::Character(_) => {
-                        if let Some(c) = text {
+            }
+
+            if last_content
+                .terminal_mode
+                .intersects(TermMode::KITTY_KEYBOARD_PROTOCOL)
+                && let Some(input) = self.kitty_input(&event, last_content.terminal_mode)
+            {
+                return (!input.is_empty())
+                    .then(|| Command::ProcessBackendCommand(BackendCommand::Write(input)));
+            }
+
+            if let Some(action) = self.keypad_action(&event, last_content.terminal_mode) {
+                binding_action = action;
+            } else {
+                match event {
+                    iced::keyboard::Event::ModifiersChanged(m) => {
+                        state.keyboard_modifiers = m;
+                        if !m.alt() {
+                            state.alt_keys = [false, false];
+                        }
+                        let action = if state.keyboard_modifiers == Modifiers::COMMAND {
+                            LinkAction::Hover
+                        } else {
+                            LinkAction::Clear
+                        };
+                        return Some(Command::ProcessBackendCommand(BackendCommand::ProcessLink(
+                            action,
+                            state.mouse_position_on_grid,
+                        )));
+                    }
+                    iced::keyboard::Event::KeyPressed {
+                        key,
+                        modifiers,
+                        text,
+                        ..
+                    } => match key.as_ref() {
+                        Key::Character(unmodified) => {
+                            if let Some(c) = text {
+                                binding_action = self.term.bindings.get_action(
+                                    InputKind::Char(c.to_ascii_lowercase()),
+                                    modifiers,
+                                    last_content.terminal_mode,
+                                );
+
+                                if binding_action == BindingAction::Ignore
+                                    && !modifiers.logo()
+                                    && !self.is_hotkey(&key, modifiers)
+                                {
+                                    let input = if modifiers.alt()
+                                        && state.is_meta(self.term.keyboard.option_as_alt)
+                                    {
+                                        // Send what the key types without Alt,
+                                        // rather than a character Alt composed
+                                        let c = if c.is_ascii() {
+                                            c.to_string()
+                                        } else if modifiers.shift() {
+                                            unmodified.to_uppercase()
+                                        } else {
+                                            unmodified.to_string()
+                                        };
+                                        self.meta_input(
+                                            &c,
+                                            modifiers.difference(Modifiers::ALT),
+                                            last_content.terminal_mode,
+                                        )
+                                    } else {
+                                        c.as_bytes().to_vec()
+                                    };
+                                    return Some(Command::ProcessBackendCommand(
+                                        BackendCommand::Write(input),
+                                    ));
+                                }
+                            }
+                        }
+                        Key::Named(_) if self.is_hotkey(&key, modifiers) => {}
+                        Key::Named(code) => {
                             binding_action = self.term.bindings.get_action(
-                                InputKind::Char(c.to_ascii_lowercase()),
+                                InputKind::KeyCode(code),
                                 modifiers,
                                 last_content.terminal_mode,
                             );
-
-                            if binding_action == BindingAction::Ignore && !modifiers.alt() && !modifiers.logo() {
-                                return Some(Command::ProcessBackendCommand(
-                                    BackendCommand::Write(c.as_bytes().to_vec()),
-                                ));
-                            }
                         }
-                    }
-                    Key::Named(code) => {
-                        binding_action = self.term.bindings.get_action(
-                            InputKind::KeyCode(code),
-                            modifiers,
-                            last_content.terminal_mode,
-                        );
-                    }
+                        _ => {}
+                    },
                     _ => {}
-                },
-                _ => {}
+                }
             }
 
             match binding_action {
@@ -321,13 +616,20 @@ impl<'a> TerminalView<'a> {
                 }
                 BindingAction::Paste => {
                     if let Some(data) = clipboard.read(ClipboardKind::Standard) {
-                        let input: Vec<u8> = data.bytes().collect();
-                        return Some(Command::ProcessBackendCommand(BackendCommand::Write(input)));
+                        return Some(Command::ProcessBackendCommand(BackendCommand::Paste(data)));
                     }
                 }
                 BindingAction::Copy => {
                     clipboard.write(ClipboardKind::Standard, backend.selectable_content());
                 }
+                BindingAction::PastePrimary => {
+                    if let Some(data) = clipboard.read(ClipboardKind::Primary) {
+                        return Some(Command::ProcessBackendCommand(BackendCommand::Paste(data)));
+                    }
+                }
+                BindingAction::CopyPrimary => {
+                    clipboard.write(ClipboardKind::Primary, backend.selectable_content());
+                }
                 // TODO: Can handle implementing the bell in the same way here
                 _ => {}
             };
@@ -337,6 +639,19 @@ impl<'a> TerminalView<'a> {
     }
 }
 
+/// The selection started by a left click of `kind` with `modifiers` held.
+/// Holding CTRL + ALT makes a single click select a block.
+fn selection_type(kind: mouse::click::Kind, modifiers: Modifiers) -> SelectionType {
+    match kind {
+        mouse::click::Kind::Single if modifiers == Modifiers::CTRL | Modifiers::ALT => {
+            SelectionType::Block
+        }
+        mouse::click::Kind::Single => SelectionType::Simple,
+        mouse::click::Kind::Double => SelectionType::Semantic,
+        mouse::click::Kind::Triple => SelectionType::Lines,
+    }
+}
+
 impl Widget<Event, Theme, iced::Renderer> for TerminalView<'_> {
     fn size(&self) -> Size<Length> {
         Size {
@@ -417,7 +732,7 @@ impl Widget<Event, Theme, iced::Renderer> for TerminalView<'_> {
                     if indexed.cell.flags.contains(cell::Flags::INVERSE)
                         || content
                             .selectable_range
-                            .map_or(false, |r| r.contains(indexed.point))
+                            .is_some_and(|r| r.contains(indexed.point))
                     {
                         std::mem::swap(&mut fg, &mut bg);
                     }
@@ -429,7 +744,7 @@ impl Widget<Event, Theme, iced::Renderer> for TerminalView<'_> {
                     frame.fill(&background, bg);
 
                     // Draw hovered hyperlink underline
-                    if content.hovered_hyperlink.as_ref().map_or(false, |range| {
+                    if content.hovered_hyperlink.as_ref().is_some_and(|range| {
                         range.contains(&indexed.point)
                             && range.contains(&state.mouse_position_on_grid)
                     }) {
@@ -530,14 +845,29 @@ impl Widget<Event, Theme, iced::Renderer> for TerminalView<'_> {
         }
 
         let commands = match event {
-            iced::Event::Mouse(mouse_event) if self.is_cursor_in_layout(cursor, layout) => {
+            // A drag keeps going when the cursor leaves the terminal
+            iced::Event::Mouse(mouse_event)
+                if state.is_dragged || self.is_cursor_in_layout(cursor, layout) =>
+            {
                 self.handle_mouse_event(
                     state,
                     layout.position(),
-                    cursor.position().unwrap(), // Assuming cursor position is always available here.
+                    cursor.position().unwrap_or_default(),
+                    clipboard,
                     mouse_event,
                 )
             }
+            iced::Event::Window(iced::window::Event::RedrawRequested(now)) => {
+                match state.auto_scroll {
+                    Some((position, due)) if now >= due => {
+                        state.auto_scroll = Some((position, now + AUTO_SCROLL_INTERVAL));
+                        vec![Command::ProcessBackendCommand(
+                            BackendCommand::SelectUpdate(position),
+                        )]
+                    }
+                    _ => Vec::new(),
+                }
+            }
             iced::Event::Keyboard(keyboard_event) => {
                 self.handle_keyboard_event(state, clipboard, keyboard_event)
                     .into_iter() // Convert Option to iterator (0 or 1 element)
@@ -546,6 +876,10 @@ impl Widget<Event, Theme, iced::Renderer> for TerminalView<'_> {
             _ => Vec::new(), // No commands for other events.
         };
 
+        if let Some((_, due)) = state.auto_scroll {
+            shell.request_redraw(iced::window::RedrawRequest::At(due));
+        }
+
         if !commands.is_empty() {
             for cmd in commands {
                 shell.publish(Event::CommandReceived(self.term.id, cmd));
@@ -571,8 +905,7 @@ impl Widget<Event, Theme, iced::Renderer> for TerminalView<'_> {
         if let Some(backend) = &self.term.backend {
             terminal_mode = backend.renderable_content().terminal_mode;
         }
-        if self.is_cursor_in_layout(cursor, layout) && !terminal_mode.contains(TermMode::SGR_MOUSE)
-        {
+        if self.is_cursor_in_layout(cursor, layout) && !Self::is_reporting(state, &terminal_mode) {
             cursor_mode = iced_core::mouse::Interaction::Text;
         }
 
@@ -594,11 +927,19 @@ impl<'a> From<TerminalView<'a>> for Element<'a, Event, Theme, iced::Renderer> {
 struct TerminalViewState {
     is_focused: bool,
     is_dragged: bool,
+    /// Whether the left and right Alt keys are held.
+    alt_keys: [bool; 2],
+    /// The button held down while the program captures the mouse.
+    reported_button: Option<mouse::Button>,
+    /// Where a selection was dragged past the edge, and when to scroll next.
+    auto_scroll: Option<((f32, f32), Instant)>,
     last_click: Option<mouse::Click>,
     scroll_pixels: f32,
     keyboard_modifiers: Modifiers,
     size: Size<f32>,
     mouse_position_on_grid: TerminalGridPoint,
+    /// The mouse position in pixels from the top left of the terminal.
+    mouse_position: (f32, f32),
 }
 
 impl TerminalViewState {
@@ -606,11 +947,26 @@ impl TerminalViewState {
         Self {
             is_focused: true,
             is_dragged: false,
+            alt_keys: [false, false],
+            reported_button: None,
+            auto_scroll: None,
             last_click: None,
             scroll_pixels: 0.0,
             keyboard_modifiers: Modifiers::empty(),
             size: Size::from([0.0, 0.0]),
             mouse_position_on_grid: TerminalGridPoint::default(),
+            mouse_position: (0.0, 0.0),
+        }
+    }
+
+    /// Whether the Alt keys held act as Meta, according to which of them
+    /// `option_as_alt` picks, instead of composing characters.
+    fn is_meta(&self, option_as_alt: [bool; 2]) -> bool {
+        let [left, right] = self.alt_keys;
+        let [left_meta, right_meta] = option_as_alt;
+        match (left, right) {
+            (false, false) => left_meta || right_meta,
+            _ => (left && left_meta) || (right && right_meta),
         }
     }
 }
@@ -634,3 +990,27 @@ impl operation::Focusable for TerminalViewState {
         self.is_focused = false;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mouse::click::Kind;
+
+    #[test]
+    fn clicks_pick_the_selection_type() {
+        let empty = Modifiers::empty();
+        assert_eq!(selection_type(Kind::Single, empty), SelectionType::Simple);
+        assert_eq!(selection_type(Kind::Double, empty), SelectionType::Semantic);
+        assert_eq!(selection_type(Kind::Triple, empty), SelectionType::Lines);
+    }
+
+    #[test]
+    fn ctrl_alt_click_selects_a_block() {
+        let ctrl_alt = Modifiers::CTRL | Modifiers::ALT;
+        assert_eq!(selection_type(Kind::Single, ctrl_alt), SelectionType::Block);
+        assert_eq!(
+            selection_type(Kind::Single, Modifiers::CTRL),
+            SelectionType::Simple
+        );
+    }
+}
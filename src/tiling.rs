@@ -0,0 +1,370 @@
+use iced::widget::pane_grid::{Axis, Configuration, Node, Pane, Split, State};
+use serde::Deserialize;
+
+/// How a workspace arranges its panes, in the spirit of dwm's layouts. Every
+/// layout but `Manual` rebuilds the whole pane grid from the workspace's pane
+/// order whenever a pane is added, removed or promoted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tiling {
+    /// Panes are only split, moved and resized by hand.
+    Manual,
+    /// Master panes on the left, every other pane stacked on the right.
+    Tile,
+    /// The focused pane fills the window.
+    Monocle,
+    Grid,
+    Columns,
+    /// Each pane takes half of the remaining space, turning clockwise.
+    Spiral,
+    /// Each pane takes half of the remaining space towards the bottom right.
+    Dwindle,
+}
+
+impl Tiling {
+    const CYCLE: [Tiling; 6] = [
+        Tiling::Tile,
+        Tiling::Monocle,
+        Tiling::Grid,
+        Tiling::Columns,
+        Tiling::Spiral,
+        Tiling::Dwindle,
+    ];
+
+    /// The layout after this one when cycling. `Manual` is left out of the
+    /// cycle since a hand made tree can't be restored once rebuilt.
+    pub fn next(self) -> Self {
+        let position = Self::CYCLE.iter().position(|tiling| *tiling == self);
+        match position {
+            Some(position) => Self::CYCLE[(position + 1) % Self::CYCLE.len()],
+            None => Tiling::Tile,
+        }
+    }
+
+    /// Builds the pane grid for `panes`, given in order with the master first.
+    /// Returns `None` for `Manual` and for an empty workspace.
+    pub fn arrange<T: Clone>(
+        self,
+        panes: &[T],
+        master_ratio: f32,
+        master_count: usize,
+    ) -> Option<Configuration<T>> {
+        if panes.is_empty() {
+            return None;
+        }
+
+        match self {
+            Tiling::Manual => None,
+            Tiling::Tile => {
+                let count = master_count.min(panes.len());
+                if count == 0 || count == panes.len() {
+                    return Some(stack(panes, Axis::Horizontal));
+                }
+                let (masters, rest) = panes.split_at(count);
+                Some(Configuration::Split {
+                    axis: Axis::Vertical,
+                    ratio: master_ratio.clamp(0.05, 0.95),
+                    a: Box::new(stack(masters, Axis::Horizontal)),
+                    b: Box::new(stack(rest, Axis::Horizontal)),
+                })
+            }
+            Tiling::Monocle | Tiling::Columns => Some(stack(panes, Axis::Vertical)),
+            Tiling::Grid => {
+                let columns = (panes.len() as f32).sqrt().ceil() as usize;
+                let mut rest = panes;
+                let columns = (0..columns)
+                    .map(|column| {
+                        let rows = rest.len() / (columns - column);
+                        let (rows, tail) = rest.split_at(rows);
+                        rest = tail;
+                        stack(rows, Axis::Horizontal)
+                    })
+                    .collect::<Vec<_>>();
+                Some(chain(columns, Axis::Vertical))
+            }
+            Tiling::Spiral | Tiling::Dwindle => Some(fibonacci(panes, self == Tiling::Spiral, 0)),
+        }
+    }
+}
+
+/// Lays `panes` out side by side along `axis` with equal sizes.
+fn stack<T: Clone>(panes: &[T], axis: Axis) -> Configuration<T> {
+    chain(
+        panes
+            .iter()
+            .map(|pane| Configuration::Pane(pane.clone()))
+            .collect(),
+        axis,
+    )
+}
+
+fn chain<T>(mut nodes: Vec<Configuration<T>>, axis: Axis) -> Configuration<T> {
+    let count = nodes.len();
+    let first = nodes.remove(0);
+    if count == 1 {
+        return first;
+    }
+
+    Configuration::Split {
+        axis,
+        ratio: 1.0 / count as f32,
+        a: Box::new(first),
+        b: Box::new(chain(nodes, axis)),
+    }
+}
+
+fn fibonacci<T: Clone>(panes: &[T], spiral: bool, depth: usize) -> Configuration<T> {
+    let pane = Configuration::Pane(panes[0].clone());
+    if panes.len() == 1 {
+        return pane;
+    }
+
+    let axis = if depth.is_multiple_of(2) {
+        Axis::Vertical
+    } else {
+        Axis::Horizontal
+    };
+    let rest = fibonacci(&panes[1..], spiral, depth + 1);
+    // A spiral places every other pair of panes after the remaining space.
+    let (a, b) = if spiral && depth % 4 >= 2 {
+        (rest, pane)
+    } else {
+        (pane, rest)
+    };
+
+    Configuration::Split {
+        axis,
+        ratio: 0.5,
+        a: Box::new(a),
+        b: Box::new(b),
+    }
+}
+
+/// The splits enclosing `pane`, nearest first, with their axis and ratio.
+pub fn ancestors(node: &Node, pane: Pane) -> Vec<(Split, Axis, f32)> {
+    match node {
+        Node::Split {
+            id,
+            axis,
+            ratio,
+            a,
+            b,
+        } => {
+            let child = if contains(a, pane) {
+                a
+            } else if contains(b, pane) {
+                b
+            } else {
+                return vec![];
+            };
+            let mut splits = ancestors(child, pane);
+            splits.push((*id, *axis, *ratio));
+            splits
+        }
+        Node::Pane(_) => vec![],
+    }
+}
+
+fn contains(node: &Node, pane: Pane) -> bool {
+    match node {
+        Node::Split { a, b, .. } => contains(a, pane) || contains(b, pane),
+        Node::Pane(other) => *other == pane,
+    }
+}
+
+/// Calls `resize` with the ratio that gives every pane along a row or column
+/// of same axis splits an equal share.
+pub fn equalize(node: &Node, resize: &mut impl FnMut(Split, f32)) {
+    if let Node::Split { id, axis, a, b, .. } = node {
+        let (a_weight, b_weight) = (weight(a, *axis), weight(b, *axis));
+        resize(*id, a_weight as f32 / (a_weight + b_weight) as f32);
+        equalize(a, resize);
+        equalize(b, resize);
+    }
+}
+
+fn weight(node: &Node, along: Axis) -> usize {
+    match node {
+        Node::Split { axis, a, b, .. } if *axis == along => weight(a, along) + weight(b, along),
+        _ => 1,
+    }
+}
+
+/// Turns the pane grid back into a configuration with the axis of the split
+/// directly holding `pane` flipped.
+pub fn rotate<T: Clone>(node: &Node, state: &State<T>, pane: Pane) -> Configuration<T> {
+    match node {
+        Node::Split {
+            axis, ratio, a, b, ..
+        } => {
+            let parent = [a, b]
+                .iter()
+                .any(|child| matches!(***child, Node::Pane(child) if child == pane));
+            let axis = match (parent, axis) {
+                (true, Axis::Horizontal) => Axis::Vertical,
+                (true, Axis::Vertical) => Axis::Horizontal,
+                (false, axis) => *axis,
+            };
+
+            Configuration::Split {
+                axis,
+                ratio: *ratio,
+                a: Box::new(rotate(a, state, pane)),
+                b: Box::new(rotate(b, state, pane)),
+            }
+        }
+        Node::Pane(pane) => Configuration::Pane(state.get(*pane).unwrap().clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Spells out a configuration as `V` or `H` splits with their ratio.
+    fn shape(configuration: &Configuration<usize>) -> String {
+        match configuration {
+            Configuration::Split { axis, ratio, a, b } => {
+                let axis = if *axis == Axis::Vertical { 'V' } else { 'H' };
+                format!("{axis}{ratio:.2}({},{})", shape(a), shape(b))
+            }
+            Configuration::Pane(id) => id.to_string(),
+        }
+    }
+
+    fn arrange(tiling: Tiling, count: usize, master_count: usize) -> Option<String> {
+        let panes: Vec<usize> = (0..count).collect();
+        tiling
+            .arrange(&panes, 0.55, master_count)
+            .map(|configuration| shape(&configuration))
+    }
+
+    #[test]
+    fn tile_puts_the_masters_beside_the_stack() {
+        assert_eq!(arrange(Tiling::Tile, 1, 1).unwrap(), "0");
+        assert_eq!(arrange(Tiling::Tile, 3, 1).unwrap(), "V0.55(0,H0.50(1,2))");
+        assert_eq!(
+            arrange(Tiling::Tile, 4, 2).unwrap(),
+            "V0.55(H0.50(0,1),H0.50(2,3))"
+        );
+        // Without a stack, or without masters, every pane shares one column.
+        assert_eq!(arrange(Tiling::Tile, 2, 2).unwrap(), "H0.50(0,1)");
+        assert_eq!(arrange(Tiling::Tile, 2, 0).unwrap(), "H0.50(0,1)");
+    }
+
+    #[test]
+    fn master_ratio_is_clamped() {
+        let shape = |ratio| shape(&Tiling::Tile.arrange(&[0, 1], ratio, 1).unwrap());
+        assert_eq!(shape(0.0), "V0.05(0,1)");
+        assert_eq!(shape(2.0), "V0.95(0,1)");
+    }
+
+    #[test]
+    fn stacked_layouts_share_space_equally_in_order() {
+        assert_eq!(
+            arrange(Tiling::Monocle, 3, 1).unwrap(),
+            "V0.33(0,V0.50(1,2))"
+        );
+        assert_eq!(
+            arrange(Tiling::Columns, 3, 1).unwrap(),
+            "V0.33(0,V0.50(1,2))"
+        );
+        assert_eq!(
+            arrange(Tiling::Grid, 4, 1).unwrap(),
+            "V0.50(H0.50(0,1),H0.50(2,3))"
+        );
+        // Columns to the right take the extra rows.
+        assert_eq!(
+            arrange(Tiling::Grid, 5, 1).unwrap(),
+            "V0.33(0,V0.50(H0.50(1,2),H0.50(3,4)))"
+        );
+    }
+
+    #[test]
+    fn spiral_turns_where_dwindle_keeps_going() {
+        assert_eq!(
+            arrange(Tiling::Dwindle, 4, 1).unwrap(),
+            "V0.50(0,H0.50(1,V0.50(2,3)))"
+        );
+        assert_eq!(
+            arrange(Tiling::Spiral, 4, 1).unwrap(),
+            "V0.50(0,H0.50(1,V0.50(3,2)))"
+        );
+    }
+
+    #[test]
+    fn manual_and_empty_workspaces_are_left_alone() {
+        assert_eq!(arrange(Tiling::Manual, 3, 1), None);
+        assert_eq!(arrange(Tiling::Tile, 0, 1), None);
+    }
+
+    #[test]
+    fn cycling_skips_manual() {
+        let mut tiling = Tiling::Manual;
+        let mut seen = vec![];
+        for _ in 0..Tiling::CYCLE.len() {
+            tiling = tiling.next();
+            seen.push(tiling);
+        }
+        assert_eq!(seen, Tiling::CYCLE);
+        assert_eq!(tiling.next(), Tiling::Tile);
+    }
+
+    /// A grid of terminals `0` to `2`, split as `V0.50(0,H0.50(1,2))`.
+    fn grid() -> State<usize> {
+        State::with_configuration(Configuration::Split {
+            axis: Axis::Vertical,
+            ratio: 0.5,
+            a: Box::new(Configuration::Pane(0)),
+            b: Box::new(Configuration::Split {
+                axis: Axis::Horizontal,
+                ratio: 0.5,
+                a: Box::new(Configuration::Pane(1)),
+                b: Box::new(Configuration::Pane(2)),
+            }),
+        })
+    }
+
+    fn find(state: &State<usize>, id: usize) -> Pane {
+        state
+            .iter()
+            .find(|(_, pane)| **pane == id)
+            .map(|(pane, _)| *pane)
+            .unwrap()
+    }
+
+    #[test]
+    fn ancestors_list_the_nearest_split_first() {
+        let state = grid();
+        let axes: Vec<Axis> = ancestors(state.layout(), find(&state, 2))
+            .into_iter()
+            .map(|(_, axis, _)| axis)
+            .collect();
+        assert_eq!(axes, [Axis::Horizontal, Axis::Vertical]);
+        assert_eq!(ancestors(state.layout(), find(&state, 0)).len(), 1);
+    }
+
+    #[test]
+    fn equalize_shares_a_row_between_its_panes() {
+        let mut state = State::with_configuration(stack(&[0, 1, 2], Axis::Vertical));
+        let first = ancestors(state.layout(), find(&state, 0))[0].0;
+        state.resize(first, 0.8);
+
+        let layout = state.layout().clone();
+        let mut ratios = vec![];
+        equalize(&layout, &mut |split, ratio| {
+            state.resize(split, ratio);
+            ratios.push(ratio);
+        });
+        assert_eq!(ratios, [1.0 / 3.0, 0.5]);
+    }
+
+    #[test]
+    fn rotate_flips_only_the_split_holding_the_pane() {
+        let state = grid();
+        let rotated = rotate(state.layout(), &state, find(&state, 1));
+        assert_eq!(shape(&rotated), "V0.50(0,V0.50(1,2))");
+        let rotated = rotate(state.layout(), &state, find(&state, 0));
+        assert_eq!(shape(&rotated), "H0.50(0,H0.50(1,2))");
+    }
+}
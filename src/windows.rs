@@ -0,0 +1,188 @@
+use iced::widget::pane_grid;
+use iced::window::settings::PlatformSpecific;
+use iced::window::{self, Level};
+use iced::{Size, Task};
+use std::collections::BTreeMap;
+
+use crate::workspace::Workspace;
+use crate::{Frostty, Message};
+
+/// A top-level window with its own set of workspaces. Terminals are owned by
+/// `Frostty`, so panes can move between windows without respawning.
+pub struct Window {
+    pub workspaces: BTreeMap<usize, Workspace>,
+    pub workspace: usize,
+    pub size: Size,
+    /// Whether the window has keyboard focus.
+    pub focused: bool,
+    /// Commands that closing the window would end, while asking about it.
+    pub confirm: Option<Vec<String>>,
+}
+
+impl Window {
+    pub fn new(index: usize, workspace: Workspace) -> Self {
+        Self {
+            workspaces: BTreeMap::from([(index, workspace)]),
+            workspace: index,
+            size: Size::ZERO,
+            focused: false,
+            confirm: None,
+        }
+    }
+
+    pub fn active(&self) -> &Workspace {
+        self.workspaces
+            .get(&self.workspace)
+            .expect("active workspace not found")
+    }
+
+    pub fn active_mut(&mut self) -> &mut Workspace {
+        self.workspaces
+            .get_mut(&self.workspace)
+            .expect("active workspace not found")
+    }
+}
+
+impl Frostty {
+    pub fn current(&self) -> &Window {
+        self.windows.get(&self.window).expect("window not found")
+    }
+
+    pub fn current_mut(&mut self) -> &mut Window {
+        self.windows
+            .get_mut(&self.window)
+            .expect("window not found")
+    }
+
+    pub fn active(&self) -> &Workspace {
+        self.current().active()
+    }
+
+    pub fn active_mut(&mut self) -> &mut Workspace {
+        self.current_mut().active_mut()
+    }
+
+    pub fn open_window(&mut self, state: Window) -> Task<Message> {
+        let config = self.config.clone().and_then(|config| config.window);
+        let mut size = Size::new(790.0, 460.0);
+        let mut level = Level::Normal;
+        let mut mode = window::Mode::Windowed;
+        let mut maximized = false;
+        if let Some(win_cfg) = config {
+            if win_cfg.level.is_some_and(|level| level == "AlwaysOnTop") {
+                level = Level::AlwaysOnTop;
+            }
+            if let Some(dim) = win_cfg.dimensions {
+                size = Size::new(dim.width, dim.height);
+            }
+            match win_cfg.mode.as_deref() {
+                Some("Fullscreen") => mode = window::Mode::Fullscreen,
+                Some("Maximized") => maximized = true,
+                _ => {}
+            }
+        }
+
+        let mut state = state;
+        state.size = size;
+        let (id, open) = window::open(window::Settings {
+            size,
+            level,
+            transparent: true,
+            // Closing asks first while programs are running.
+            exit_on_close_request: false,
+            platform_specific: PlatformSpecific {
+                application_id: "frostty".to_string(),
+                override_redirect: false,
+            },
+            icon: Some(
+                iced::window::icon::from_file_data(
+                    include_bytes!("../extra/icons/hicolor/24x24/apps/frostty.png"),
+                    None,
+                )
+                .unwrap(),
+            ),
+            ..Default::default()
+        });
+        self.windows.insert(id, state);
+        self.window = id;
+
+        let focus = match self.active().focus {
+            Some(pane) => self.focus_pane(pane),
+            None => Task::none(),
+        };
+        Task::batch([
+            open.discard(),
+            window::change_mode(id, mode),
+            window::maximize(id, maximized),
+            focus,
+        ])
+    }
+
+    /// Moves the focused pane into a window of its own.
+    pub fn tear_off(&mut self) -> Task<Message> {
+        if let Some(pane) = self.active().focus
+            && !self.active().is_pinned(pane)
+            && (self.active().panes.len() > 1 || self.current().workspaces.len() > 1)
+        {
+            let (window, index) = (self.window, self.current().workspace);
+            let (pane, task) = self.take_pane(window, index, pane);
+            let workspace = self.workspace(pane_grid::Configuration::Pane(pane), None, self.tiling);
+            return Task::batch([task, self.open_window(Window::new(1, workspace))]);
+        }
+        Task::none()
+    }
+
+    /// Moves the focused pane to the next window, following it there.
+    pub fn move_to_next_window(&mut self) -> Task<Message> {
+        let target = self
+            .windows
+            .range(self.window..)
+            .nth(1)
+            .or_else(|| self.windows.iter().next())
+            .map(|(id, _)| *id)
+            .filter(|id| *id != self.window)
+            .filter(|id| self.windows[id].active().accepts_pane());
+        if let Some(target) = target
+            && let Some(pane) = self.active().focus
+            && !self.active().is_pinned(pane)
+        {
+            let (window, index) = (self.window, self.current().workspace);
+            let (pane, task) = self.take_pane(window, index, pane);
+            self.window = target;
+            match self.add_pane(pane.clone()) {
+                Some(pane) => {
+                    return Task::batch([task, window::gain_focus(target), self.focus_pane(pane)]);
+                }
+                None => self.kill_pane(pane),
+            }
+            return task;
+        }
+        Task::none()
+    }
+
+    /// Drops the terminals of a closed window, which shuts down local shells
+    /// and detaches from daemon sessions.
+    pub fn window_closed(&mut self, id: window::Id) -> Task<Message> {
+        if let Some(window) = self.windows.remove(&id) {
+            for workspace in window.workspaces.values() {
+                for (_, pane) in workspace.panes.iter() {
+                    for id in &pane.tabs {
+                        self.terminals.remove(&(*id as u64));
+                    }
+                }
+                // The scratchpad only hides along with its window.
+                for float in &workspace.floating {
+                    if Some(float.pane.id) != self.scratchpad {
+                        self.terminals.remove(&(float.pane.id as u64));
+                    }
+                }
+            }
+        }
+        match self.windows.keys().next() {
+            Some(window) if id == self.window => self.window = *window,
+            Some(_) => {}
+            None => return iced::exit(),
+        }
+        Task::none()
+    }
+}
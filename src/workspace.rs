@@ -0,0 +1,506 @@
+use iced::time::Instant;
+use iced::widget::pane_grid;
+use iced::{Task, window};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::floating::Float;
+use crate::tiling::Tiling;
+use crate::{Frostty, Message, Monitor, config, layout, terminal};
+
+/// One of the numbered sets of panes in a window.
+pub struct Workspace {
+    pub panes: pane_grid::State<Pane>,
+    pub focus: Option<pane_grid::Pane>,
+    /// Terminals drawn above the grid, bottom to top.
+    pub floating: Vec<Float>,
+    /// Terminal id of the floating terminal with focus, which takes
+    /// precedence over `focus`.
+    pub floating_focus: Option<usize>,
+    /// Terminal ids in tiling order, master first.
+    pub order: Vec<usize>,
+    pub tiling: Tiling,
+    pub master_ratio: f32,
+    pub master_count: usize,
+}
+
+impl Workspace {
+    pub fn find(&self, id: usize) -> Option<pane_grid::Pane> {
+        self.panes
+            .iter()
+            .find(|(_, pane)| pane.id == id)
+            .map(|(pane, _)| *pane)
+    }
+
+    pub fn is_pinned(&self, pane: pane_grid::Pane) -> bool {
+        self.panes.get(pane).is_some_and(|pane| pane.is_pinned)
+    }
+
+    /// Rewrites the pane order with `unpinned` while pinned panes keep their
+    /// positions.
+    pub fn place(&mut self, unpinned: Vec<usize>) {
+        let pinned: HashSet<usize> = self
+            .panes
+            .iter()
+            .filter(|(_, pane)| pane.is_pinned)
+            .map(|(_, pane)| pane.id)
+            .collect();
+        let mut slots: BTreeMap<usize, usize> = self
+            .order
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| pinned.contains(id))
+            .map(|(position, id)| (position, *id))
+            .collect();
+        let mut unpinned = unpinned.into_iter();
+
+        self.order.clear();
+        while !slots.is_empty() || unpinned.len() > 0 {
+            let next = match slots.remove(&self.order.len()) {
+                Some(id) => Some(id),
+                None => unpinned
+                    .next()
+                    .or_else(|| slots.pop_first().map(|(_, id)| id)),
+            };
+            self.order.extend(next);
+        }
+    }
+
+    /// The pane order without pinned panes.
+    pub fn unpinned(&self) -> Vec<usize> {
+        self.order
+            .iter()
+            .copied()
+            .filter(|id| self.find(*id).is_none_or(|pane| !self.is_pinned(pane)))
+            .collect()
+    }
+
+    pub fn focused(&self) -> Option<usize> {
+        self.focus
+            .and_then(|pane| self.panes.get(pane))
+            .map(|pane| pane.id)
+    }
+
+    pub fn arrange(&mut self) {
+        self.arrange_with(None);
+    }
+
+    /// Whether [`Frostty::add_pane`] has somewhere to put a pane, which a
+    /// manual layout only has while a pane is focused to split.
+    pub fn accepts_pane(&self) -> bool {
+        self.tiling != Tiling::Manual || self.focus.is_some()
+    }
+
+    /// Switches to tiling by hand before a split is resized or rotated, since
+    /// arranging again would undo the change. Returns false in `Monocle`,
+    /// which only shows the focused pane and so has nothing to change.
+    pub fn tile_by_hand(&mut self) -> bool {
+        if self.tiling == Tiling::Monocle {
+            return false;
+        }
+        self.tiling = Tiling::Manual;
+        true
+    }
+
+    /// Rebuilds the pane grid from `order` unless tiling by hand. `added` is a
+    /// pane in `order` that isn't in the grid yet. Focus stays on the same
+    /// terminal.
+    fn arrange_with(&mut self, added: Option<Pane>) {
+        let mut panes: HashMap<usize, Pane> = self
+            .panes
+            .iter()
+            .map(|(_, pane)| (pane.id, pane.clone()))
+            .chain(added.map(|pane| (pane.id, pane)))
+            .collect();
+        let clients: Vec<Pane> = self
+            .order
+            .iter()
+            .filter_map(|id| panes.remove(id))
+            .collect();
+        let Some(configuration) =
+            self.tiling
+                .arrange(&clients, self.master_ratio, self.master_count)
+        else {
+            return;
+        };
+        self.rebuild(configuration);
+    }
+
+    /// Replaces the pane grid with `configuration`, keeping focus and zoom on
+    /// the same terminals.
+    pub fn rebuild(&mut self, configuration: pane_grid::Configuration<Pane>) {
+        let focused = self.focused();
+        let zoomed = self
+            .panes
+            .maximized()
+            .and_then(|pane| self.panes.get(pane))
+            .map(|pane| pane.id);
+        self.panes = pane_grid::State::with_configuration(configuration);
+        self.focus = focused
+            .and_then(|id| self.find(id))
+            .or_else(|| self.order.first().and_then(|id| self.find(*id)));
+        let zoomed = match self.tiling {
+            Tiling::Monocle => self.focus,
+            _ => zoomed.and_then(|id| self.find(id)),
+        };
+        if let Some(pane) = zoomed {
+            self.panes.maximize(pane);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Pane {
+    /// The terminal shown in the pane.
+    pub id: usize,
+    /// Terminals stacked as tabs in the pane, including `id`.
+    pub tabs: Vec<usize>,
+    pub is_pinned: bool,
+    /// Part of the broadcast set while broadcasting to marked panes.
+    pub marked: bool,
+    pub monitor: Option<Monitor>,
+    /// The monitor fired while the pane was in the background.
+    pub alert: bool,
+    /// When the visual bell last rang, while it is fading.
+    pub bell: Option<Instant>,
+    /// The bell rang since the pane last had focus.
+    pub rang: bool,
+    /// The terminal's shell exited while the pane was pinned.
+    pub exited: bool,
+    /// The command running in the shown terminal while asking whether to
+    /// close it.
+    pub confirm: Option<String>,
+    /// Commands running in the other panes while asking whether to close
+    /// them.
+    pub confirm_others: Option<Vec<String>>,
+    /// A paste into the shown terminal waiting to be confirmed.
+    pub paste: Option<String>,
+}
+
+impl Pane {
+    pub fn new(id: usize) -> Self {
+        Self {
+            id,
+            tabs: vec![id],
+            is_pinned: false,
+            marked: false,
+            monitor: None,
+            alert: false,
+            bell: None,
+            rang: false,
+            exited: false,
+            confirm: None,
+            confirm_others: None,
+            paste: None,
+        }
+    }
+}
+
+impl Frostty {
+    /// Finds the window, workspace and pane that hold the terminal with the given id.
+    pub fn locate(&self, id: u64) -> Option<(window::Id, usize, pane_grid::Pane)> {
+        self.windows.iter().find_map(|(window, state)| {
+            state.workspaces.iter().find_map(|(index, workspace)| {
+                workspace
+                    .panes
+                    .iter()
+                    .find(|(_, pane)| pane.tabs.contains(&(id as usize)))
+                    .map(|(pane, _)| (*window, *index, *pane))
+            })
+        })
+    }
+
+    /// The lowest workspace number not yet used in the focused window.
+    pub fn free_workspace(&self) -> usize {
+        (1..)
+            .find(|index| !self.current().workspaces.contains_key(index))
+            .unwrap()
+    }
+
+    pub fn layout(&self, predicate: impl Fn(&config::Layout) -> bool) -> Option<config::Layout> {
+        self.config
+            .as_ref()
+            .and_then(|config| config.layouts.as_ref())
+            .and_then(|layouts| layouts.iter().find(|layout| predicate(layout)))
+            .cloned()
+    }
+
+    /// Creates a workspace from `configuration`, whose panes are ordered left
+    /// to right for tiling.
+    pub fn workspace(
+        &self,
+        configuration: pane_grid::Configuration<Pane>,
+        focus: Option<usize>,
+        tiling: Tiling,
+    ) -> Workspace {
+        let mut order = vec![];
+        leaves(&configuration, &mut order);
+        let mut workspace = Workspace {
+            panes: pane_grid::State::with_configuration(configuration),
+            focus: None,
+            floating: vec![],
+            floating_focus: None,
+            order,
+            tiling,
+            master_ratio: self.master_ratio,
+            master_count: self.master_count,
+        };
+        workspace.focus = focus
+            .or(workspace.order.first().copied())
+            .and_then(|id| workspace.find(id));
+        workspace.arrange();
+        workspace
+    }
+
+    pub fn shell_workspace(&mut self, index: usize) -> Workspace {
+        let id = self.spawn_terminal(self.term_settings.backend.clone(), index);
+        self.workspace(
+            pane_grid::Configuration::Pane(Pane::new(id)),
+            None,
+            self.tiling,
+        )
+    }
+
+    /// Builds a workspace of side by side panes attached to daemon sessions.
+    pub fn session_workspace(&mut self, sessions: &[u64], index: usize) -> Workspace {
+        let panes: Vec<Pane> = sessions
+            .iter()
+            .map(|session| {
+                let settings = terminal::settings::BackendSettings {
+                    session: Some(terminal::settings::Session::Attach(*session)),
+                    ..self.term_settings.backend.clone()
+                };
+                Pane::new(self.spawn_terminal(settings, index))
+            })
+            .collect();
+        let configuration = Tiling::Columns.arrange(&panes, 0.5, 1).unwrap();
+        self.workspace(configuration, None, self.tiling)
+    }
+
+    pub fn layout_workspace(&mut self, layout: &config::Layout, index: usize) -> Workspace {
+        let mut first = None;
+        let mut focused = None;
+        let configuration = layout::build(&layout.root, &mut |leaf| {
+            let settings = layout::backend_settings(leaf, &self.term_settings.backend);
+            let id = self.spawn_terminal(settings, index);
+            first.get_or_insert(id);
+            if leaf.focus.unwrap_or(false) {
+                focused = Some(id);
+            }
+            Pane::new(id)
+        });
+
+        // A declared layout is kept as written until the tiling is cycled.
+        self.workspace(configuration, focused.or(first), Tiling::Manual)
+    }
+
+    /// Adds a pane to the focused workspace. Tiled workspaces take it as their
+    /// new master, while manual ones split the focused pane along its longer
+    /// side.
+    pub fn add_pane(&mut self, pane: Pane) -> Option<pane_grid::Pane> {
+        let id = pane.id;
+        let size = self.current().size;
+        let workspace = self.active_mut();
+        if workspace.tiling != Tiling::Manual {
+            let mut unpinned = workspace.unpinned();
+            unpinned.insert(0, id);
+            workspace.place(unpinned);
+            workspace.arrange_with(Some(pane));
+            return workspace.find(id);
+        }
+
+        let focus = workspace.focus?;
+        let region = *workspace
+            .panes
+            .layout()
+            .pane_regions(0.0, size)
+            .get(&focus)?;
+        let axis = if region.width >= region.height {
+            pane_grid::Axis::Vertical
+        } else {
+            pane_grid::Axis::Horizontal
+        };
+        let (split, _) = workspace.panes.split(axis, focus, pane)?;
+        let position = workspace
+            .panes
+            .get(focus)
+            .and_then(|focus| workspace.order.iter().position(|id| *id == focus.id))
+            .map_or(workspace.order.len(), |position| position + 1);
+        workspace.order.insert(position, id);
+        Some(split)
+    }
+
+    pub fn close_pane(
+        &mut self,
+        window: window::Id,
+        index: usize,
+        pane: pane_grid::Pane,
+    ) -> Task<Message> {
+        let (closed, task) = self.take_pane(window, index, pane);
+        self.kill_pane(closed);
+        task
+    }
+
+    /// Ends the terminals of a pane that was taken out of its workspace.
+    pub fn kill_pane(&mut self, pane: Pane) {
+        for id in pane.tabs {
+            self.kill_terminal(id as u64);
+        }
+    }
+
+    /// Removes a pane from its workspace and returns it with its terminal
+    /// still running. A workspace left empty goes with it, and so does a
+    /// window left without workspaces.
+    pub fn take_pane(
+        &mut self,
+        window: window::Id,
+        index: usize,
+        pane: pane_grid::Pane,
+    ) -> (Pane, Task<Message>) {
+        let focused = window == self.window;
+        let state = self.windows.get_mut(&window).unwrap();
+        let workspace = state.workspaces.get_mut(&index).unwrap();
+        if let Some((taken, sibling)) = workspace.panes.close(pane) {
+            workspace.focus = Some(sibling);
+            workspace.order.retain(|id| *id != taken.id);
+            workspace.arrange();
+            if focused
+                && index == state.workspace
+                && let Some(pane) = workspace.focus
+            {
+                return (taken, self.focus_pane(pane));
+            }
+            return (taken, Task::none());
+        }
+
+        let taken = workspace.panes.get(pane).unwrap().clone();
+        let removed = state.workspaces.remove(&index).unwrap();
+        for float in removed.floating {
+            if Some(float.pane.id) != self.scratchpad {
+                self.kill_terminal(float.pane.id as u64);
+            }
+        }
+        let state = self.windows.get_mut(&window).unwrap();
+        if state.workspaces.is_empty() {
+            self.windows.remove(&window);
+            if let Some(next) = self.windows.keys().next()
+                && focused
+            {
+                self.window = *next;
+            }
+            return (taken, window::close(window));
+        }
+
+        // The last pane of a workspace is leaving, so the workspace goes with it.
+        if index == state.workspace {
+            state.workspace = state
+                .workspaces
+                .range(..index)
+                .next_back()
+                .or_else(|| state.workspaces.range(index..).next())
+                .map(|(index, _)| *index)
+                .unwrap();
+            if focused && let Some(pane) = self.active().focus {
+                return (taken, self.focus_pane(pane));
+            }
+        }
+        (taken, Task::none())
+    }
+}
+
+/// Collects the terminal ids of a configuration's panes from left to right.
+fn leaves(configuration: &pane_grid::Configuration<Pane>, ids: &mut Vec<usize>) {
+    match configuration {
+        pane_grid::Configuration::Split { a, b, .. } => {
+            leaves(a, ids);
+            leaves(b, ids);
+        }
+        pane_grid::Configuration::Pane(pane) => ids.push(pane.id),
+    }
+}
+
+#[cfg(test)]
+impl Workspace {
+    /// A workspace tiled with `tiling` holding terminals `ids` in order.
+    pub fn tiled(tiling: Tiling, ids: &[usize]) -> Self {
+        let panes: Vec<Pane> = ids.iter().map(|id| Pane::new(*id)).collect();
+        let mut workspace = Workspace {
+            panes: pane_grid::State::new(panes[0].clone()).0,
+            focus: None,
+            floating: vec![],
+            floating_focus: None,
+            order: ids.to_vec(),
+            tiling,
+            master_ratio: 0.5,
+            master_count: 1,
+        };
+        workspace.rebuild(Tiling::Tile.arrange(&panes, 0.5, 1).unwrap());
+        workspace.arrange();
+        workspace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zoomed(workspace: &Workspace) -> Option<usize> {
+        workspace
+            .panes
+            .maximized()
+            .and_then(|pane| workspace.panes.get(pane))
+            .map(|pane| pane.id)
+    }
+
+    #[test]
+    fn zoom_stays_on_its_terminal_when_rearranged() {
+        let mut workspace = Workspace::tiled(Tiling::Tile, &[0, 1, 2]);
+        workspace.panes.maximize(workspace.find(2).unwrap());
+        workspace.place(vec![2, 0, 1]);
+        workspace.arrange();
+        assert_eq!(zoomed(&workspace), Some(2));
+    }
+
+    #[test]
+    fn monocle_zooms_whichever_pane_has_focus() {
+        let mut workspace = Workspace::tiled(Tiling::Monocle, &[0, 1, 2]);
+        assert_eq!(zoomed(&workspace), Some(0));
+
+        workspace.focus = workspace.find(1);
+        workspace.arrange();
+        assert_eq!(zoomed(&workspace), Some(1));
+    }
+
+    #[test]
+    fn resizing_by_hand_leaves_the_tiling_to_the_user() {
+        let mut tiled = Workspace::tiled(Tiling::Tile, &[0, 1]);
+        assert!(tiled.tile_by_hand());
+        assert_eq!(tiled.tiling, Tiling::Manual);
+
+        let mut monocle = Workspace::tiled(Tiling::Monocle, &[0, 1]);
+        assert!(!monocle.tile_by_hand());
+        assert_eq!(monocle.tiling, Tiling::Monocle);
+    }
+
+    fn pin(workspace: &mut Workspace, id: usize) {
+        let pane = workspace.find(id).unwrap();
+        workspace.panes.get_mut(pane).unwrap().is_pinned = true;
+    }
+
+    #[test]
+    fn pinned_panes_keep_their_place_in_the_order() {
+        let mut workspace = Workspace::tiled(Tiling::Tile, &[0, 1, 2, 3]);
+        pin(&mut workspace, 1);
+        assert_eq!(workspace.unpinned(), [0, 2, 3]);
+
+        workspace.place(vec![3, 2, 0]);
+        assert_eq!(workspace.order, [3, 1, 2, 0]);
+    }
+
+    #[test]
+    fn pinned_panes_close_up_behind_fewer_panes() {
+        let mut workspace = Workspace::tiled(Tiling::Tile, &[0, 1, 2]);
+        pin(&mut workspace, 2);
+        workspace.place(vec![1]);
+        assert_eq!(workspace.order, [1, 2]);
+    }
+}